@@ -0,0 +1,73 @@
+//! Benchmarks searching a large corpus for many literal patterns at once.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use minigrep::{CaseMode, PatternMode, SearchOptions};
+use std::hint::black_box;
+
+const PATTERN_COUNT: usize = 1_000;
+const LINE_COUNT: usize = 50_000;
+
+/// Builds a deny-list of identifiers like `user_0042`.
+fn patterns() -> Vec<String> {
+    (0..PATTERN_COUNT).map(|i| format!("user_{i:04}")).collect()
+}
+
+/// Builds a log-like corpus where roughly one line in twenty mentions an
+/// identifier from the deny-list.
+fn corpus() -> String {
+    // Small linear congruential generator so the corpus is the same every run
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize
+    };
+
+    let words = [
+        "request", "handled", "payment", "timeout", "retry", "session", "opened", "closed",
+    ];
+    let mut corpus = String::new();
+
+    for line in 0..LINE_COUNT {
+        corpus.push_str(&format!("2024-01-01T00:00:{:02} INFO", line % 60));
+        for _ in 0..8 {
+            corpus.push(' ');
+            corpus.push_str(words[next() % words.len()]);
+        }
+        // Identifiers outside the deny-list share its prefix
+        corpus.push_str(&format!(" user_{:04}\n", next() % (PATTERN_COUNT * 20)));
+    }
+
+    corpus
+}
+
+fn literal_search(c: &mut Criterion) {
+    let corpus = corpus();
+    let mut group = c.benchmark_group("1000 literal patterns");
+    group.sample_size(10);
+
+    for (name, case_mode) in [
+        ("case-sensitive", CaseMode::Sensitive),
+        ("ignore-case", CaseMode::Insensitive),
+    ] {
+        let searcher = SearchOptions::new("")
+            .patterns(patterns())
+            .pattern_mode(PatternMode::Literal)
+            .case_mode(case_mode)
+            .build()
+            .unwrap();
+
+        group.bench_function(format!("count {name}"), |b| {
+            b.iter(|| searcher.count_matches(black_box(&corpus)))
+        });
+        group.bench_function(format!("search {name}"), |b| {
+            b.iter(|| searcher.search(black_box(&corpus)).len())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, literal_search);
+criterion_main!(benches);
@@ -1,9 +1,16 @@
 //! Command-line argument parsing for minigrep.
 use crate::{
     core,
-    models::{Config, ConfigError, ContextFlag},
+    models::{
+        CaseMode, Config, ConfigError, ContextSpec, MatchBoundary, Normalization, OutputMode,
+        PatternMode, TableColumn, TableFormat,
+    },
 };
-use std::{env, mem, path, process};
+use std::{env, fs, io, mem, path, process};
+
+/// Separates paragraphs for `--paragraph`: a line break followed by one or
+/// more blank lines, which may contain spaces or tabs.
+const PARAGRAPH_SEPARATOR: &str = r"\r?\n(?:[ \t]*\r?\n)+";
 
 /// Parses command-line arguments into a Config object.
 ///
@@ -30,7 +37,7 @@ where
     if args_vec.len() <= 1 {
         println!("Error: Not enough arguments\n");
         core::print_help();
-        process::exit(1);
+        process::exit(2);
     }
 
     if args_vec.len() > 1 && (args_vec[1] == "--help" || args_vec[1] == "-h") {
@@ -48,36 +55,223 @@ where
     }
 
     // Core arguments
-    let query = mem::take(&mut args_vec[1]);
-    let file_path_1 = mem::take(&mut args_vec[2]);
+    // When the first argument gives patterns with a flag such as -e or --query
+    // there is no query argument, and the file paths can appear anywhere
+    let (query, mut file_path_1, mut i) = if is_pattern_flag(&args_vec[1]) {
+        (String::new(), String::new(), 1)
+    } else {
+        (mem::take(&mut args_vec[1]), mem::take(&mut args_vec[2]), 3)
+    };
 
     // Default values
     let mut file_path_2 = "".to_string();
-    let mut ignore_case = env::var("IGNORE_CASE").is_ok();
-    let mut context_flag = ContextFlag::After;
-    let mut context_count = 0;
+    let mut patterns = Vec::new();
+    let mut boolean_query = None;
+    let mut all_of = Vec::new();
+    let mut none_of = Vec::new();
+    let mut near = None;
+    let mut multiline = false;
+    let mut multiline_dotall = false;
+    let mut fuzzy = None;
+    let mut replace = None;
+    let mut in_place = false;
+    let mut backup = false;
+    let mut dry_run = false;
+    let mut case_mode = if env::var("IGNORE_CASE").is_ok() {
+        CaseMode::Insensitive
+    } else {
+        CaseMode::Sensitive
+    };
+    let mut pattern_mode = PatternMode::Auto;
+    let mut invert = false;
+    let mut boundary = MatchBoundary::None;
+    let mut normalization = Normalization::None;
+    let mut ignore_accents = false;
+    let mut before_count = None;
+    let mut after_count = None;
+    let mut context_count = None;
+    let mut group_separator = Some("--".to_string());
     let mut show_stats = false;
     let mut recursive = false;
+    let mut output_mode = OutputMode::Lines;
+    let mut quiet = false;
+    let mut max_count = None;
+    let mut max_total = None;
+    let mut only_matching = false;
+    let mut line_number = false;
+    let mut with_filename = false;
+    let mut column = false;
+    let mut byte_offset = false;
+    let mut crlf = false;
+    let mut preserve_line_endings = false;
+    let mut null = false;
+    let mut null_data = false;
+    let mut record_separator = None;
+    let mut json_fields = Vec::new();
+    let mut fields = Vec::new();
+    let mut pretty = false;
+    let mut table_format = None;
+    let mut table_column = None;
+    let mut csv_output = false;
+    let mut json = false;
 
     // Process remaining arguments
     // Supported formats:
     // 1. minigrep <query> <file>
-    // 2. minigrep <query> <file> -i/-S --ignore-case/--case-sensitive (case options)
+    // 2. minigrep <query> <file> -i/-S --ignore-case/--case-sensitive/--smart-case
+    //    (case options, the last one given overrides the IGNORE_CASE env variable)
     // 3. minigrep <query> <file> --stats/-s (statistics)
-    // 4. minigrep <query> <file> --context/-c/--before/-b/--after/-a [count]
+    // 4. minigrep <query> <file> --context/-C/--before/-B/--after/-A [count]
+    //    [--group-separator SEP/--no-group-separator]
     // 5. minigrep <query> <directory> --recursive/-r (recursive search)
     // 6. minigrep <query> <file1> <file2> (multiple files)
+    // 7. minigrep <query> <file> --only-matching/-o [-n/--line-number] [-H/--with-filename]
+    //    [--column] [-b/--byte-offset] (also without -o)
+    // 8. minigrep <query> <file> --count/-c/--count-matches (per-file counts)
+    // 9. minigrep <query> <file> --files-with-matches/-l/--files-without-match/-L
+    // 10. minigrep <query> <file> --quiet/-q (no output, exit status only)
+    // 11. minigrep <query> <file> --max-count/-m N/--max-total N (early termination)
+    // 12. minigrep <query> <file> -F/-E --fixed-strings/--regex (pattern interpretation)
+    // 13. minigrep <query> <file> --invert-match/-w/-x --word-regexp/--line-regexp
+    // 14. minigrep <query> <file> --normalize[=]nfc|nfkc/--ignore-accents (Unicode matching)
+    // 15. minigrep -e/--regexp PATTERN [-e PATTERN]... -f/--file FILE <file> (multiple patterns)
+    // 16. minigrep --query 'TERM AND (TERM OR NOT "some phrase")' <file> (boolean query)
+    // 17. minigrep <query> <file> --all-of PATTERN/--none-of PATTERN (file-level filters)
+    // 18. minigrep -e PATTERN -e PATTERN <file> --near N (patterns within N lines)
+    // 19. minigrep <query> <file> -U/--multiline/--multiline-dotall (matches across lines)
+    // 20. minigrep <query> <file> --fuzzy N (approximate matching, up to N edits)
+    // 21. minigrep <query> <file> --replace TEMPLATE (preview replacements)
+    // 22. minigrep <query> <file> --replace TEMPLATE --in-place [--backup] [--dry-run]
+    //     (rewrite files)
+    // 23. minigrep <query> <file> --crlf/--preserve-line-endings (Windows line endings)
+    // 24. minigrep <query> <file> -0/--null/--null-data (NUL-separated names and records)
+    // 25. minigrep <query> <file> --record-separator REGEX/--paragraph (multi-line records)
+    // 26. minigrep --json-field FIELD=VALUE [--json-field FIELD~REGEX]... <file>
+    //     [--fields FIELD,FIELD] [--pretty] (JSON lines)
+    // 27. minigrep <query> <file> --csv/--tsv [--column-name NAME/--column-index N]
+    //     [--csv-output] (delimited tables)
+    // 28. minigrep <query> <file> --json (results as JSON lines)
     //
-    // All these options can be combined in any order after the query and first file
+    // All these options can be combined in any order after the query and first file,
+    // or anywhere when the first argument is -e, -f, --query, --all-of, --none-of or
+    // --json-field
 
-    let mut i = 3;
     while i < args_vec.len() {
         let arg = &args_vec[i];
 
         match arg.as_str() {
             // Case sensitivity flags
-            "-i" | "--ignore-case" => ignore_case = true,
-            "-S" | "--case-sensitive" => ignore_case = false,
+            "-i" | "--ignore-case" => case_mode = CaseMode::Insensitive,
+            "-S" | "--case-sensitive" => case_mode = CaseMode::Sensitive,
+            "--smart-case" => case_mode = CaseMode::Smart,
+
+            // Pattern interpretation flags
+            "-F" | "--fixed-strings" => pattern_mode = PatternMode::Literal,
+            "-E" | "--regex" => pattern_mode = PatternMode::Regex,
+
+            // Matching flags
+            "--invert-match" => invert = true,
+            "-w" | "--word-regexp" => boundary = MatchBoundary::Word,
+            "-x" | "--line-regexp" => boundary = MatchBoundary::Line,
+
+            // Pattern flags, each adds patterns to the query
+            "-e" | "--regexp" => {
+                let pattern = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                patterns.push(pattern.clone());
+                i += 1; // Skip the pattern argument
+            }
+            "-f" | "--file" => {
+                let pattern_file = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                patterns.extend(read_pattern_file(pattern_file)?);
+                i += 1; // Skip the pattern file argument
+            }
+
+            // Boolean query flag, replaces the query
+            "--query" => {
+                let expression = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                boolean_query = Some(expression.clone());
+                i += 1; // Skip the query argument
+            }
+
+            // File-level pattern flags
+            "--all-of" | "--none-of" => {
+                let pattern = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                if arg == "--all-of" {
+                    all_of.push(pattern.clone());
+                } else {
+                    none_of.push(pattern.clone());
+                }
+                i += 1; // Skip the pattern argument
+            }
+
+            // Proximity flag
+            "--near" => {
+                let value = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                let distance = value
+                    .parse::<usize>()
+                    .map_err(|_| ConfigError::InvalidNearDistance(value.clone()))?;
+                near = Some(distance);
+                i += 1; // Skip the distance argument
+            }
+
+            // Fuzzy matching flag
+            "--fuzzy" => {
+                let value = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                let distance = value
+                    .parse::<usize>()
+                    .map_err(|_| ConfigError::InvalidFuzzyDistance(value.clone()))?;
+                fuzzy = Some(distance);
+                i += 1; // Skip the distance argument
+            }
+
+            // Replacement flag
+            "--replace" => {
+                let template = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                replace = Some(template.clone());
+                i += 1; // Skip the template argument
+            }
+
+            // In-place rewriting flags
+            "--in-place" => in_place = true,
+            "--backup" => backup = true,
+            "--dry-run" => dry_run = true,
+
+            // Line ending flag
+            "--crlf" => crlf = true,
+
+            // Multiline flags
+            "-U" | "--multiline" => multiline = true,
+            "--multiline-dotall" => {
+                multiline = true;
+                multiline_dotall = true;
+            }
+
+            // Unicode normalization flags
+            "--normalize" => {
+                let form = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                normalization = parse_normalization(form)?;
+                i += 1; // Skip the normalization form argument
+            }
+            _ if arg.starts_with("--normalize=") => {
+                normalization = parse_normalization(&arg["--normalize=".len()..])?;
+            }
+            "--ignore-accents" => ignore_accents = true,
 
             // Stats flag
             "--stats" | "-s" => show_stats = true,
@@ -85,53 +279,120 @@ where
             // Recursive flag
             "--recursive" | "-r" => recursive = true,
 
-            // Context flags
-            "--before" | "-b" => {
-                context_flag = ContextFlag::Before;
-                // Check for context count in next argument
-                if i + 1 < args_vec.len() {
-                    if let Ok(count) = args_vec[i + 1].parse::<u8>() {
-                        context_count = count;
-                        i += 1; // Skip the next argument
-                    } else {
-                        context_count = 1; // Default count
-                    }
+            // Summary output flags (the last one given wins)
+            "--count" | "-c" => output_mode = OutputMode::Count,
+            "--count-matches" => output_mode = OutputMode::CountMatches,
+            "--files-with-matches" | "-l" => output_mode = OutputMode::FilesWithMatches,
+            "--files-without-match" | "-L" => output_mode = OutputMode::FilesWithoutMatch,
+
+            // Quiet flag
+            "--quiet" | "-q" => quiet = true,
+
+            // Early termination flags
+            "--max-count" | "-m" | "--max-total" => {
+                let value = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                let count = value
+                    .parse::<usize>()
+                    .map_err(|_| ConfigError::InvalidMaxCount(value.clone()))?;
+
+                if arg == "--max-total" {
+                    max_total = Some(count);
                 } else {
-                    context_count = 1; // Default count
+                    max_count = Some(count);
                 }
+                i += 1; // Skip the count argument
             }
 
-            "--after" | "-a" => {
-                context_flag = ContextFlag::After;
-                // Check for context count in next argument
-                if i + 1 < args_vec.len() {
-                    if let Ok(count) = args_vec[i + 1].parse::<u8>() {
-                        context_count = count;
-                        i += 1; // Skip the next argument
-                    } else {
-                        context_count = 1; // Default count
-                    }
-                } else {
-                    context_count = 1; // Default count
-                }
+            // Only-matching output flags
+            "--only-matching" | "-o" => only_matching = true,
+            "--line-number" | "-n" => line_number = true,
+            "--with-filename" | "-H" => with_filename = true,
+            "--column" => column = true,
+            "--byte-offset" | "-b" => byte_offset = true,
+            "--preserve-line-endings" => preserve_line_endings = true,
+            "--null" | "-0" => null = true,
+            "--null-data" => null_data = true,
+
+            // Record flags
+            "--record-separator" => {
+                let separator = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                record_separator = Some(separator.clone());
+                i += 1; // Skip the separator argument
             }
+            "--paragraph" => record_separator = Some(PARAGRAPH_SEPARATOR.to_string()),
 
-            "--context" | "-c" => {
-                context_flag = ContextFlag::Context;
-                // Check for context count in next argument
-                if i + 1 < args_vec.len() {
-                    if let Ok(count) = args_vec[i + 1].parse::<u8>() {
-                        context_count = count;
-                        i += 1; // Skip the next argument
-                    } else {
-                        context_count = 1; // Default count
-                    }
-                } else {
-                    context_count = 1; // Default count
-                }
+            // JSON lines flags
+            "--json-field" => {
+                let predicate = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                json_fields.push(predicate.clone());
+                i += 1; // Skip the predicate argument
+            }
+            "--fields" => {
+                let list = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                fields.extend(
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|field| !field.is_empty())
+                        .map(str::to_string),
+                );
+                i += 1; // Skip the field list
+            }
+            "--pretty" => pretty = true,
+
+            // Table flags
+            "--csv" => table_format = Some(TableFormat::Csv),
+            "--tsv" => table_format = Some(TableFormat::Tsv),
+            "--column-name" => {
+                let name = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                table_column = Some(TableColumn::Name(name.clone()));
+                i += 1; // Skip the name argument
+            }
+            "--column-index" => {
+                let value = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                // Columns are numbered from 1 on the command line
+                let index = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| index.checked_sub(1))
+                    .ok_or_else(|| ConfigError::InvalidColumnIndex(value.clone()))?;
+                table_column = Some(TableColumn::Index(index));
+                i += 1; // Skip the index argument
+            }
+            "--csv-output" => csv_output = true,
+            "--json" => json = true,
+
+            // Context flags
+            // --before and --after take precedence over --context regardless of order
+            "--before" | "-B" => before_count = Some(next_context_count(&args_vec, &mut i)),
+            "--after" | "-A" => after_count = Some(next_context_count(&args_vec, &mut i)),
+            "--context" | "-C" => context_count = Some(next_context_count(&args_vec, &mut i)),
+
+            // Group separator flags
+            "--group-separator" => {
+                let separator = args_vec
+                    .get(i + 1)
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                group_separator = Some(separator.clone());
+                i += 1; // Skip the separator argument
             }
+            "--no-group-separator" => group_separator = None,
 
-            // If we get here, assume it's a second file path if it's empty
+            // If we get here, assume it's a file path if one is still missing
+            _ if file_path_1.is_empty() && !arg.starts_with("-") => {
+                file_path_1 = std::mem::take(&mut args_vec[i]);
+            }
             _ if file_path_2.is_empty() && !arg.starts_with("-") => {
                 file_path_2 = std::mem::take(&mut args_vec[i]);
             }
@@ -143,6 +404,146 @@ where
         i += 1;
     }
 
+    if file_path_1.is_empty() {
+        return Err(ConfigError::NotEnoughArguments);
+    }
+
+    if boolean_query.is_some() && (!query.is_empty() || !patterns.is_empty()) {
+        return Err(ConfigError::ConflictingQuery);
+    }
+
+    // Field predicates can select JSON lines without any pattern
+    if query.is_empty() && patterns.is_empty() && boolean_query.is_none() && json_fields.is_empty()
+    {
+        // File-level patterns only choose the files, the lines still need a pattern
+        if !all_of.is_empty() {
+            return Err(ConfigError::RequiresPattern("--all-of".to_string()));
+        }
+        if !none_of.is_empty() {
+            return Err(ConfigError::RequiresPattern("--none-of".to_string()));
+        }
+        // A pattern file without any patterns would leave nothing to search for
+        return Err(ConfigError::NoPatterns);
+    }
+
+    // Within as many edits as a pattern has characters, any text would match it
+    if let Some(distance) = fuzzy {
+        let terms = match &boolean_query {
+            // An invalid query is reported when the search is built
+            Some(boolean_query) => core::BooleanQuery::parse(boolean_query)
+                .map(|boolean_query| boolean_query.terms)
+                .unwrap_or_default(),
+            None if query.contains('|') => query.split('|').map(|p| p.trim().to_string()).collect(),
+            None => vec![query.clone()],
+        };
+        let too_short = terms
+            .iter()
+            .chain(&patterns)
+            .find(|pattern| !pattern.is_empty() && pattern.chars().count() <= distance);
+        if let Some(pattern) = too_short {
+            return Err(ConfigError::FuzzyDistanceTooLarge(
+                distance,
+                pattern.clone(),
+            ));
+        }
+    }
+
+    if in_place && replace.is_none() {
+        return Err(ConfigError::InPlaceWithoutReplace);
+    }
+    if in_place {
+        // Files are only rewritten when printing lines, and records must
+        // start and end on line boundaries for the diff of a dry run
+        let conflicting = match output_mode {
+            OutputMode::Count => Some("--count"),
+            OutputMode::CountMatches => Some("--count-matches"),
+            OutputMode::FilesWithMatches => Some("--files-with-matches"),
+            OutputMode::FilesWithoutMatch => Some("--files-without-match"),
+            OutputMode::Lines if quiet => Some("--quiet"),
+            OutputMode::Lines if null_data => Some("--null-data"),
+            OutputMode::Lines
+                if record_separator
+                    .as_deref()
+                    .is_some_and(|separator| separator != PARAGRAPH_SEPARATOR) =>
+            {
+                Some("--record-separator")
+            }
+            OutputMode::Lines => None,
+        };
+        if let Some(flag) = conflicting {
+            return Err(ConfigError::ConflictingFlags(
+                "--in-place".to_string(),
+                flag.to_string(),
+            ));
+        }
+    }
+    if json {
+        // JSON objects replace the printed lines, but not counts or file names
+        let conflicting = match output_mode {
+            OutputMode::Count => Some("--count"),
+            OutputMode::CountMatches => Some("--count-matches"),
+            OutputMode::FilesWithMatches => Some("--files-with-matches"),
+            OutputMode::FilesWithoutMatch => Some("--files-without-match"),
+            OutputMode::Lines if only_matching => Some("--only-matching"),
+            OutputMode::Lines if in_place => Some("--in-place"),
+            OutputMode::Lines if csv_output => Some("--csv-output"),
+            OutputMode::Lines if !fields.is_empty() => Some("--fields"),
+            OutputMode::Lines if pretty => Some("--pretty"),
+            OutputMode::Lines => None,
+        };
+        if let Some(flag) = conflicting {
+            return Err(ConfigError::ConflictingFlags(
+                "--json".to_string(),
+                flag.to_string(),
+            ));
+        }
+    }
+    // Record separators aren't line endings, so they can't be preserved
+    if preserve_line_endings && let Some(separator) = &record_separator {
+        let flag = if separator == PARAGRAPH_SEPARATOR {
+            "--paragraph"
+        } else {
+            "--record-separator"
+        };
+        return Err(ConfigError::ConflictingFlags(
+            "--preserve-line-endings".to_string(),
+            flag.to_string(),
+        ));
+    }
+    if invert && near.is_some() {
+        return Err(ConfigError::ConflictingFlags(
+            "--invert-match".to_string(),
+            "--near".to_string(),
+        ));
+    }
+    if !in_place {
+        if backup {
+            return Err(ConfigError::RequiresInPlace("--backup".to_string()));
+        }
+        if dry_run {
+            return Err(ConfigError::RequiresInPlace("--dry-run".to_string()));
+        }
+    }
+
+    if table_format.is_none() {
+        match table_column {
+            Some(TableColumn::Name(_)) => {
+                return Err(ConfigError::RequiresTableFormat(
+                    "--column-name".to_string(),
+                ));
+            }
+            Some(TableColumn::Index(_)) => {
+                return Err(ConfigError::RequiresTableFormat(
+                    "--column-index".to_string(),
+                ));
+            }
+            None if csv_output => {
+                return Err(ConfigError::RequiresTableFormat("--csv-output".to_string()));
+            }
+            None => {}
+        }
+    }
+
     // Verify directory if recursive
     if recursive {
         let path = path::Path::new(&file_path_1);
@@ -153,12 +554,124 @@ where
 
     Ok(Config {
         query,
+        patterns,
+        boolean_query,
+        all_of,
+        none_of,
+        near,
+        multiline,
+        multiline_dotall,
+        fuzzy,
+        replace,
+        in_place,
+        backup,
+        dry_run,
+        crlf,
+        null_data,
+        record_separator,
+        json_fields,
+        table_format,
+        table_column,
         file_path_1,
         file_path_2,
-        ignore_case,
-        context_flag,
-        context_count,
+        pattern_mode,
+        case_mode,
+        invert,
+        boundary,
+        normalization,
+        ignore_accents,
+        context: ContextSpec::new(
+            before_count.or(context_count).unwrap_or(0),
+            after_count.or(context_count).unwrap_or(0),
+        ),
+        group_separator,
         show_stats,
         recursive,
+        output_mode,
+        quiet,
+        max_count,
+        max_total,
+        only_matching,
+        line_number,
+        with_filename,
+        column,
+        byte_offset,
+        preserve_line_endings,
+        null,
+        fields,
+        pretty,
+        csv_output,
+        json,
     })
 }
+
+/// Reads the optional line count following a context flag.
+///
+/// If the next argument is a number it is consumed and returned, otherwise
+/// the count defaults to 1.
+///
+/// # Arguments
+///
+/// * `args_vec` - All command-line arguments
+/// * `i` - Index of the context flag, advanced past the count if one is present
+fn next_context_count(args_vec: &[String], i: &mut usize) -> usize {
+    match args_vec.get(*i + 1).map(|arg| arg.parse::<usize>()) {
+        Some(Ok(count)) => {
+            *i += 1; // Skip the count argument
+            count
+        }
+        _ => 1, // Default count
+    }
+}
+
+/// Parses the normalization form given to `--normalize`.
+///
+/// # Arguments
+///
+/// * `form` - The normalization form, `nfc` or `nfkc` in any case
+///
+/// # Errors
+///
+/// Returns an error if the form is not recognized.
+fn parse_normalization(form: &str) -> Result<Normalization, ConfigError> {
+    match form.to_ascii_lowercase().as_str() {
+        "nfc" => Ok(Normalization::Nfc),
+        "nfkc" => Ok(Normalization::Nfkc),
+        _ => Err(ConfigError::InvalidNormalization(form.to_string())),
+    }
+}
+
+/// Returns true if the argument gives patterns in place of the query argument.
+fn is_pattern_flag(arg: &str) -> bool {
+    matches!(
+        arg,
+        "-e" | "--regexp" | "-f" | "--file" | "--query" | "--all-of" | "--none-of" | "--json-field"
+    )
+}
+
+/// Reads patterns from a file, one per line, or from stdin if the path is `-`.
+///
+/// Blank lines are skipped, unlike grep which reads them as empty patterns
+/// matching every line.
+///
+/// # Arguments
+///
+/// * `pattern_file` - Path of the file holding the patterns
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read.
+fn read_pattern_file(pattern_file: &str) -> Result<Vec<String>, ConfigError> {
+    let contents = if pattern_file == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(pattern_file)
+    }
+    .map_err(|e| ConfigError::UnreadablePatternFile(pattern_file.to_string(), e.to_string()))?;
+
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(String::from)
+        .collect())
+}
@@ -1,7 +1,7 @@
 //! Functionality for displaying search results with formatting and highlighting.
 
-use crate::models::SearchResult;
-use regex::Regex;
+use super::json_field;
+use crate::models::{Config, LineEnding, SearchResult};
 use std::io::Write;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
@@ -11,160 +11,323 @@ use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 ///
 /// * `file_label` - Name or path of the file containing the matches
 /// * `results` - Search results to display
-/// * `ignore_case` - Whether the search was case-insensitive
-pub fn display_results(file_label: &str, results: &[SearchResult], ignore_case: bool) {
-    if results.is_empty() {
-        println!("{file_label}: No matches found.");
+/// * `config` - The search configuration controlling the output format
+pub fn display_results(file_label: &str, results: &[SearchResult], config: &Config) {
+    if config.json {
+        for result in results {
+            display_json_result(file_label, result);
+        }
+    } else if config.csv_output {
+        display_table_rows(results);
+    } else if config.only_matching {
+        for result in results {
+            display_only_matching(file_label, result, config);
+        }
+    } else if results.is_empty() {
+        println!(
+            "{file_label}{} No matches found.",
+            file_name_separator(config)
+        );
     } else {
-        println!("Matches in {file_label}:");
+        println!("Matches in {file_label}{}", file_name_separator(config));
+
+        // Separate non-adjacent groups of lines when context or proximity
+        // windows are shown
+        let separator = config
+            .group_separator
+            .as_deref()
+            .filter(|_| !config.context.is_empty() || config.near.is_some());
+        let mut previous_line_number = None;
+
         for result in results {
-            display_search_result(result, ignore_case);
+            if let (Some(separator), Some(previous)) = (separator, previous_line_number)
+                && result.get_line_number() > previous + 1
+            {
+                println!("{separator}");
+            }
+
+            display_search_result(result, config);
+            previous_line_number = Some(result.get_line_number());
         }
     }
 }
 
-/// Displays a single search result with highlighted matches.
+/// Displays the header row and matching rows of a table exactly as they
+/// appear in the file, so that the output is itself a valid table.
+///
+/// Context rows are left out, and nothing is printed without matches.
 ///
 /// # Arguments
 ///
-/// * `search_result` - The search result to display
-/// * `ignore_case` - Whether the search was case-insensitive
-fn display_search_result(search_result: &SearchResult, ignore_case: bool) {
-    let mut stdout = StandardStream::stdout(ColorChoice::Always);
-    let mut highlight_spec = ColorSpec::new();
-    highlight_spec.set_fg(Some(Color::Cyan)).set_bold(true);
+/// * `results` - Search results of a table, starting with its header row
+fn display_table_rows(results: &[SearchResult]) {
+    for result in results {
+        if result.get_line_number() == 0 || !result.is_context() {
+            println!("{}", result.get_line_content());
+        }
+    }
+}
 
-    // Print the line number
-    print!("Line {}: ", search_result.get_line_number() + 1);
+/// Displays the match count for a specific file.
+///
+/// # Arguments
+///
+/// * `file_label` - Name or path of the file that was searched
+/// * `count` - Number of matching lines or individual matches in the file
+/// * `config` - The search configuration controlling the separator
+pub fn display_count(file_label: &str, count: usize, config: &Config) {
+    println!("{file_label}{}{count}", file_name_separator(config));
+}
 
-    // Check if there are any matching patterns
-    let matching_patterns = search_result.get_matching_patterns();
-    if matching_patterns.is_empty() {
-        // No patterns to highlight, just print the line
-        println!("{}", search_result.get_line_content());
-        return;
+/// Displays the name of a file selected by `--files-with-matches` or
+/// `--files-without-match`.
+///
+/// With `--null` the name ends with NUL instead of a line break, so that
+/// names containing line breaks can be read back safely.
+///
+/// # Arguments
+///
+/// * `file_label` - Name or path of the file to print
+/// * `config` - The search configuration controlling the terminator
+pub fn display_file_name(file_label: &str, config: &Config) {
+    if config.null {
+        print!("{file_label}\0");
+    } else {
+        println!("{file_label}");
     }
+}
+
+/// Returns the character printed right after a file name: `:`, or NUL with
+/// `--null`.
+fn file_name_separator(config: &Config) -> char {
+    if config.null { '\0' } else { ':' }
+}
+
+/// Displays a unified diff, coloring removed lines red, added lines green
+/// and hunk headers cyan.
+///
+/// # Arguments
+///
+/// * `diff` - The diff to display
+pub fn display_diff(diff: &str) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
 
-    // Check if the pattern might be a regex
-    let pattern = &matching_patterns[0];
-    let regex_indicators = [
-        '*', '+', '?', '.', '\\', '[', ']', '(', ')', '{', '}', '^', '$',
-    ];
-    let might_be_regex = pattern.chars().any(|c| regex_indicators.contains(&c));
-
-    if might_be_regex {
-        // Try to compile the regex for highlighting
-        let regex_result = if ignore_case {
-            regex::RegexBuilder::new(pattern)
-                .case_insensitive(true)
-                .build()
+    for line in diff.lines() {
+        let color = if line.starts_with("---") || line.starts_with("+++") {
+            None
+        } else if line.starts_with("@@") {
+            Some(Color::Cyan)
+        } else if line.starts_with('-') {
+            Some(Color::Red)
+        } else if line.starts_with('+') {
+            Some(Color::Green)
         } else {
-            Regex::new(pattern)
+            None
         };
 
-        if let Ok(regex) = regex_result {
-            // Highlight regex matches
-            let line = &search_result.get_line_content();
-            let mut last_match_end = 0;
-
-            // Find all matches and highlight them
-            for captures in regex.captures_iter(line) {
-                let m = captures.get(0).unwrap();
+        let _ = stdout.set_color(ColorSpec::new().set_fg(color));
+        let _ = write!(&mut stdout, "{line}");
+        let _ = stdout.reset();
+        let _ = writeln!(&mut stdout);
+    }
+}
 
-                // Print text before match
-                let before_match = &line[last_match_end..m.start()];
-                let _ = write!(&mut stdout, "{}", before_match);
+/// Displays a single search result with highlighted matches.
+///
+/// Matching lines are marked with `:` after the line number and context
+/// lines with `-`, as grep does. Records separated by `--record-separator`
+/// are numbered as records instead of lines, and the rows of a table as
+/// rows. The column of the first match and the byte offset of the line
+/// follow the line number when requested, and the edit distance of each
+/// match follows the marker in fuzzy mode, as in `Line 3: ~1 ...`.
+///
+/// Lines holding JSON are shown projected to `--fields` or pretty-printed
+/// with `--pretty` when requested, without highlighting.
+///
+/// # Arguments
+///
+/// * `search_result` - The search result to display
+/// * `config` - The search configuration controlling the prefix
+fn display_search_result(search_result: &SearchResult, config: &Config) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
 
-                // Print highlighted match
-                let _ = stdout.set_color(&highlight_spec);
-                let match_text = &line[m.start()..m.end()];
-                let _ = write!(&mut stdout, "{}", match_text);
-                let _ = stdout.reset();
+    // Print the line number with a match or context marker
+    let marker = if search_result.is_context() { '-' } else { ':' };
+    let unit = if config.table_format.is_some() {
+        "Row"
+    } else if config.record_separator.is_some() {
+        "Record"
+    } else {
+        "Line"
+    };
+    print!("{} {}", unit, search_result.get_line_number() + 1);
+    if config.column
+        && let Some(column) = search_result.get_column()
+    {
+        print!(", column {column}");
+    }
+    if config.byte_offset {
+        print!(", byte {}", search_result.get_byte_offset());
+    }
+    print!("{marker} ");
 
-                last_match_end = m.end();
-            }
+    // In fuzzy mode, the edit distance of each match follows the marker
+    let distances = search_result.get_edit_distances();
+    if !distances.is_empty() {
+        let distances: Vec<String> = distances.iter().map(usize::to_string).collect();
+        print!("~{} ", distances.join(","));
+    }
 
-            // Print remaining text
-            if last_match_end < line.len() {
-                let remaining = &line[last_match_end..];
-                let _ = write!(&mut stdout, "{}", remaining);
-            }
+    let line = search_result.get_line_content();
 
-            println!();
-        } else {
-            // Fall back to normal display if regex is invalid
-            println!("{}", search_result.get_line_content());
-        }
+    if let Some(json) = format_json_line(line, config) {
+        let _ = write!(&mut stdout, "{json}");
     } else {
-        // Find all matches for all patterns
-        let mut matches = Vec::new();
-
-        for pattern in search_result.get_matching_patterns() {
-            let pattern_matches = if ignore_case {
-                let pattern_lower = pattern.to_lowercase();
-                let content_lower = search_result.get_line_content().to_lowercase();
-
-                let mut indices = Vec::new();
-                let mut start = 0;
-                while let Some(position) = content_lower[start..].find(&pattern_lower) {
-                    let absolute_position = start + position;
-                    // (start of match, end of match)
-                    indices.push((absolute_position, absolute_position + pattern_lower.len()));
-                    start = absolute_position + 1;
-                }
-                indices
-            } else {
-                search_result
-                    .get_line_content()
-                    .match_indices(pattern)
-                    .map(|(start, part)| (start, start + part.len()))
-                    .collect::<Vec<_>>()
-            };
-
-            matches.extend(pattern_matches);
-        }
-
-        // Sort matches by start position
-        matches.sort_by_key(|&(start, _)| start);
-
-        // Merge overlapping matches
-        let mut merged_matches = Vec::new();
-        for (start, end) in matches {
-            if let Some((_, prev_end)) = merged_matches.last_mut() {
-                // If this match overlaps with previous, merge them
-                if start <= *prev_end {
-                    *prev_end = end.max(*prev_end);
-                } else {
-                    merged_matches.push((start, end));
-                }
-            } else {
-                merged_matches.push((start, end));
-            }
-        }
-
-        // Print with highlighting
         let mut last_index = 0;
-        for (start, end) in merged_matches {
+
+        for &(start, end) in search_result.get_match_spans() {
             // Text before match
-            let before_match = &search_result.get_line_content()[last_index..start];
-            let _ = write!(&mut stdout, "{}", before_match);
+            let _ = write!(&mut stdout, "{}", &line[last_index..start]);
 
             // Highlighted match
-            let _ = stdout.set_color(&highlight_spec);
-            let match_text = &search_result.get_line_content()[start..end];
-            let _ = write!(&mut stdout, "{}", match_text);
+            let _ = stdout.set_color(&highlight_spec());
+            let _ = write!(&mut stdout, "{}", &line[start..end]);
             let _ = stdout.reset();
 
             last_index = end;
         }
 
         // Remaining text
-        let remaining = &search_result.get_line_content()[last_index..];
-        let _ = write!(&mut stdout, "{}", remaining);
-        println!();
+        let _ = write!(&mut stdout, "{}", &line[last_index..]);
+    }
+
+    // A last line without a terminator still ends the output line, so that
+    // whatever is printed next starts on a line of its own
+    match search_result.get_line_ending() {
+        ending if config.preserve_line_endings && ending != LineEnding::None => {
+            let _ = write!(&mut stdout, "{}", ending.as_str());
+        }
+        _ => println!(),
+    }
+}
+
+/// Displays a search result as a JSON object on a line of its own.
+///
+/// The object holds the file, whether the line is a match or context, its
+/// one-based line number, its byte offset, its text and its matches. Each
+/// match has its `start` and `end` byte offsets in the text, and its
+/// `edit_distance` in fuzzy mode.
+///
+/// # Arguments
+///
+/// * `file_label` - Name or path of the file containing the line
+/// * `search_result` - The search result to display
+fn display_json_result(file_label: &str, search_result: &SearchResult) {
+    let line = search_result.get_line_content();
+    let distances = search_result.get_edit_distances();
+    let matches: Vec<serde_json::Value> = search_result
+        .get_match_spans()
+        .iter()
+        .enumerate()
+        .map(|(index, &(start, end))| {
+            let mut m = serde_json::json!({
+                "start": start,
+                "end": end,
+                "text": &line[start..end],
+            });
+            if let Some(distance) = distances.get(index) {
+                m["edit_distance"] = (*distance).into();
+            }
+            m
+        })
+        .collect();
+
+    let object = serde_json::json!({
+        "file": file_label,
+        "type": if search_result.is_context() { "context" } else { "match" },
+        "line_number": search_result.get_line_number() + 1,
+        "byte_offset": search_result.get_byte_offset(),
+        "text": line,
+        "matches": matches,
+    });
+    println!("{object}");
+}
+
+/// Formats a line holding JSON for `--fields` and `--pretty`.
+///
+/// # Returns
+///
+/// * `Option<String>` - The projected or pretty-printed JSON, or `None` if
+///   neither is requested or the line isn't valid JSON
+fn format_json_line(line: &str, config: &Config) -> Option<String> {
+    if config.fields.is_empty() && !config.pretty {
+        return None;
+    }
+
+    let mut value: serde_json::Value = serde_json::from_str(line).ok()?;
+    if !config.fields.is_empty() {
+        value = json_field::project(&value, &config.fields);
+    }
+
+    if config.pretty {
+        serde_json::to_string_pretty(&value).ok()
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Displays only the matched parts of a search result, one match per line.
+///
+/// Each match is optionally prefixed with the file label (`--with-filename`),
+/// the line number (`--line-number`), its one-based character column
+/// (`--column`) and its byte offset in the file (`--byte-offset`). Context
+/// lines have no matches and produce no output.
+///
+/// # Arguments
+///
+/// * `file_label` - Name or path of the file containing the matches
+/// * `search_result` - The search result to extract matches from
+/// * `config` - The search configuration controlling the prefixes
+fn display_only_matching(file_label: &str, search_result: &SearchResult, config: &Config) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
+    let line = search_result.get_line_content();
+
+    // Empty matches have nothing to print, and the prefixes give where the
+    // match was before any replacement
+    let spans = search_result
+        .get_match_spans()
+        .iter()
+        .zip(search_result.get_match_positions())
+        .filter(|((start, end), _)| start < end);
+
+    for (&(start, end), (column, offset)) in spans {
+        if config.with_filename {
+            let _ = write!(&mut stdout, "{}{}", file_label, file_name_separator(config));
+        }
+        if config.line_number {
+            let _ = write!(&mut stdout, "{}:", search_result.get_line_number() + 1);
+        }
+        if config.column {
+            let _ = write!(&mut stdout, "{}:", column);
+        }
+        if config.byte_offset {
+            let _ = write!(&mut stdout, "{}:", search_result.get_byte_offset() + offset);
+        }
+
+        let _ = stdout.set_color(&highlight_spec());
+        let _ = write!(&mut stdout, "{}", &line[start..end]);
+        let _ = stdout.reset();
+        let _ = writeln!(&mut stdout);
     }
 }
 
+/// Returns the color used to highlight matched text.
+fn highlight_spec() -> ColorSpec {
+    let mut highlight_spec = ColorSpec::new();
+    highlight_spec.set_fg(Some(Color::Cyan)).set_bold(true);
+    highlight_spec
+}
+
 /// Prints help information about the minigrep tool.
 pub fn print_help() {
     let mut stdout = StandardStream::stdout(ColorChoice::Always);
@@ -230,6 +393,14 @@ pub fn print_help() {
         &mut stdout,
         "    minigrep PATTERN FILENAME [SECOND_FILENAME] [OPTIONS]"
     );
+    let _ = writeln!(
+        &mut stdout,
+        "    minigrep -e PATTERN [-e PATTERN]... FILENAME [SECOND_FILENAME] [OPTIONS]"
+    );
+    let _ = writeln!(
+        &mut stdout,
+        "    minigrep --query EXPR FILENAME [SECOND_FILENAME] [OPTIONS]"
+    );
     let _ = writeln!(&mut stdout);
 
     // Arguments
@@ -273,6 +444,174 @@ pub fn print_help() {
         "Force case-sensitive search (overrides IGNORE_CASE env)",
         "SEARCH OPTIONS",
     );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--smart-case",
+        "Ignore case unless the pattern contains uppercase letters",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--fixed-strings, -F",
+        "Treat PATTERN as literal text, even with regex characters",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--regex, -E",
+        "Treat PATTERN as a regular expression",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--invert-match",
+        "Select lines that do not match",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--word-regexp, -w",
+        "Only match whole words",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--line-regexp, -x",
+        "Only match whole lines",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--normalize FORM",
+        "Normalize PATTERN and lines to Unicode form nfc or nfkc",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--ignore-accents",
+        "Ignore accents on letters (cafe matches café)",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "-e PATTERN",
+        "Also search for PATTERN, may be repeated (not split on |)",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "-f FILE",
+        "Read patterns from FILE, one per line (- for stdin)",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--query EXPR",
+        "Search with a boolean query, e.g. 'ERROR AND NOT retry'",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--all-of PATTERN",
+        "Only search files where PATTERN occurs, may be repeated",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--none-of PATTERN",
+        "Skip files where PATTERN occurs, may be repeated",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--fuzzy N",
+        "Match literally, allowing up to N edited characters",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--crlf",
+        "Let '$' match before \\r\\n line endings in regex patterns",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--null-data",
+        "Search NUL-terminated records instead of lines",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--record-separator REGEX",
+        "Search records separated by REGEX instead of lines",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--paragraph",
+        "Search paragraphs separated by blank lines",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--json-field PRED",
+        "Only match JSON lines where PRED holds, e.g. level=error, msg~timeout, ms>=500",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--csv, --tsv",
+        "Search the rows of a CSV or TSV table, showing its header row",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--column-name NAME",
+        "Only match within the table column named NAME in the header",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--column-index N",
+        "Only match within the Nth table column, counting from 1",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--multiline, -U",
+        "Let matches span several lines, printing every line they cover",
+        "SEARCH OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--multiline-dotall",
+        "Like --multiline, and '.' also matches line breaks",
+        "SEARCH OPTIONS",
+    );
     let _ = writeln!(&mut stdout);
 
     // Context options
@@ -280,22 +619,43 @@ pub fn print_help() {
     print_option(
         &mut stdout,
         &cyan,
-        "--before N, -b N",
+        "--before N, -B N",
         "Show N lines before each match",
         "CONTEXT OPTIONS",
     );
     print_option(
         &mut stdout,
         &cyan,
-        "--after N, -a N",
+        "--after N, -A N",
         "Show N lines after each match",
         "CONTEXT OPTIONS",
     );
     print_option(
         &mut stdout,
         &cyan,
-        "--context N, -c N",
-        "Show N lines before and after each match",
+        "--context N, -C N",
+        "Show N lines before and after each match (-B/-A override it)",
+        "CONTEXT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--group-separator SEP",
+        "Print SEP between non-adjacent groups of lines (default: --)",
+        "CONTEXT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--no-group-separator",
+        "Don't print a separator between groups of lines",
+        "CONTEXT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--near N",
+        "Match where all patterns occur within N lines",
         "CONTEXT OPTIONS",
     );
     let _ = writeln!(&mut stdout);
@@ -309,6 +669,160 @@ pub fn print_help() {
         "Display search statistics",
         "OUTPUT OPTIONS",
     );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--count, -c",
+        "Print only the number of matching lines per file",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--count-matches",
+        "Print only the number of individual matches per file",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--files-with-matches, -l",
+        "Print only the names of files containing a match",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--files-without-match, -L",
+        "Print only the names of files without a match",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--quiet, -q",
+        "Print nothing, exit with status 0 on the first match",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--max-count N, -m N",
+        "Stop after N matching lines in each file",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--max-total N",
+        "Stop the whole search after N matching lines",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--only-matching, -o",
+        "Print only the matched parts of each line, one per line",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--line-number, -n",
+        "Prefix --only-matching output with the line number",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--with-filename, -H",
+        "Prefix --only-matching output with the file path",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--column",
+        "Show the 1-based character column of the first match",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--byte-offset, -b",
+        "Show the byte offset of each line (of each match with -o)",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--preserve-line-endings",
+        "End printed lines with their original \\n or \\r\\n",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--null, -0",
+        "Follow file names with NUL instead of ':' or a line break",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--replace TEMPLATE",
+        "Show matches replaced by TEMPLATE ($1, ${name}), files are unchanged",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--in-place",
+        "Write the --replace replacements back to the files",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--backup",
+        "Keep a FILE.bak copy of every file rewritten by --in-place",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--dry-run",
+        "Print a unified diff of what --in-place would change",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--fields LIST",
+        "Print only these comma-separated fields of JSON lines",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--pretty",
+        "Pretty-print JSON lines",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--csv-output",
+        "Print the header and matching rows of a table as plain CSV or TSV",
+        "OUTPUT OPTIONS",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "--json",
+        "Print each matching or context line as a JSON object, with its matches",
+        "OUTPUT OPTIONS",
+    );
     let _ = writeln!(&mut stdout);
 
     // Directory options
@@ -370,6 +884,20 @@ pub fn print_help() {
         "Show context around matches",
         "EXAMPLES",
     );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "minigrep \"\\d+\" log.txt -o -n",
+        "Print each match with its line number",
+        "EXAMPLES",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "minigrep TODO src -r -l",
+        "List files containing a match",
+        "EXAMPLES",
+    );
     print_option(
         &mut stdout,
         &cyan,
@@ -391,6 +919,13 @@ pub fn print_help() {
         "Combine multiple options",
         "EXAMPLES",
     );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "minigrep --json-field level=error app.log --fields ts,msg",
+        "Show the time and message of JSON error logs",
+        "EXAMPLES",
+    );
     let _ = writeln!(&mut stdout);
 
     // Regex examples
@@ -442,13 +977,20 @@ pub fn print_help() {
         &mut stdout,
         &cyan,
         "0",
-        "Successful execution",
+        "At least one match was found",
         "EXIT CODES",
     );
     print_option(
         &mut stdout,
         &cyan,
         "1",
+        "No matches were found",
+        "EXIT CODES",
+    );
+    print_option(
+        &mut stdout,
+        &cyan,
+        "2",
         "Error occurred (invalid arguments, file not found, etc.)",
         "EXIT CODES",
     );
@@ -0,0 +1,197 @@
+//! Parses and evaluates field predicates such as `level=error` or
+//! `latency_ms>=500` against lines holding JSON objects.
+//!
+//! A predicate is a dotted field path, an operator and a value. Path
+//! segments name object keys or, for arrays, zero-based indices. The
+//! operators are `=` and `!=` for equality, `~` and `!~` for regex matches,
+//! and `<`, `<=`, `>` and `>=` for numeric comparison.
+
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::{
+    error,
+    io::{self, ErrorKind},
+};
+
+/// How a field is compared with the value of a predicate.
+#[derive(Debug)]
+enum Operator {
+    Equal(String),
+    NotEqual(String),
+    Matches(Regex),
+    NotMatches(Regex),
+    Less(f64),
+    LessOrEqual(f64),
+    Greater(f64),
+    GreaterOrEqual(f64),
+}
+
+/// A condition on one field of a JSON object.
+#[derive(Debug)]
+pub struct FieldPredicate {
+    /// The keys and array indices leading to the field
+    path: Vec<String>,
+    operator: Operator,
+}
+
+impl FieldPredicate {
+    /// Parses a predicate such as `level=error`, `msg~time(out|d out)` or
+    /// `status>=500`.
+    ///
+    /// The operator is the first one in the text, so the value may contain
+    /// operator characters itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The predicate text
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predicate has no operator or field, or its
+    /// value isn't a valid regex or number for the operator.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn error::Error>> {
+        let Some(position) = input.find(['=', '!', '~', '<', '>']) else {
+            return Err(predicate_error(input, "missing operator"));
+        };
+        let field = &input[..position];
+        let rest = &input[position..];
+
+        // Two-character operators are checked first so `>=` isn't read as `>`
+        let (symbol, value) = ["!=", "!~", "<=", ">=", "=", "~", "<", ">"]
+            .into_iter()
+            .find_map(|symbol| rest.strip_prefix(symbol).map(|value| (symbol, value)))
+            .ok_or_else(|| predicate_error(input, "missing operator"))?;
+
+        if field.is_empty() {
+            return Err(predicate_error(input, "missing field"));
+        }
+
+        let regex = || {
+            Regex::new(value).map_err(|e| predicate_error(input, &format!("invalid regex: {e}")))
+        };
+        let number = || {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| predicate_error(input, &format!("'{value}' is not a number")))
+        };
+
+        let operator = match symbol {
+            "=" => Operator::Equal(value.to_string()),
+            "!=" => Operator::NotEqual(value.to_string()),
+            "~" => Operator::Matches(regex()?),
+            "!~" => Operator::NotMatches(regex()?),
+            "<" => Operator::Less(number()?),
+            "<=" => Operator::LessOrEqual(number()?),
+            ">" => Operator::Greater(number()?),
+            _ => Operator::GreaterOrEqual(number()?),
+        };
+
+        Ok(Self {
+            path: split_path(field),
+            operator,
+        })
+    }
+
+    /// Checks the predicate against a JSON value.
+    ///
+    /// A missing field only satisfies the negated operators `!=` and `!~`.
+    /// Numeric comparisons also accept strings holding a number.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The parsed JSON line
+    pub fn is_match(&self, value: &Value) -> bool {
+        let Some(field) = lookup(value, &self.path) else {
+            return matches!(
+                self.operator,
+                Operator::NotEqual(_) | Operator::NotMatches(_)
+            );
+        };
+
+        match &self.operator {
+            Operator::Equal(expected) => equals(field, expected),
+            Operator::NotEqual(expected) => !equals(field, expected),
+            Operator::Matches(regex) => regex.is_match(&field_text(field)),
+            Operator::NotMatches(regex) => !regex.is_match(&field_text(field)),
+            Operator::Less(bound) => as_number(field).is_some_and(|n| n < *bound),
+            Operator::LessOrEqual(bound) => as_number(field).is_some_and(|n| n <= *bound),
+            Operator::Greater(bound) => as_number(field).is_some_and(|n| n > *bound),
+            Operator::GreaterOrEqual(bound) => as_number(field).is_some_and(|n| n >= *bound),
+        }
+    }
+}
+
+/// Builds an object holding only the given fields of a JSON value, keyed by
+/// their paths as written and in the order given.
+///
+/// Missing fields are set to `null`, so every projected line has the same keys.
+///
+/// # Arguments
+///
+/// * `value` - The parsed JSON line
+/// * `fields` - Dotted paths of the fields to keep
+pub fn project(value: &Value, fields: &[String]) -> Value {
+    let projected: Map<String, Value> = fields
+        .iter()
+        .map(|field| {
+            let selected = lookup(value, &split_path(field))
+                .cloned()
+                .unwrap_or(Value::Null);
+            (field.clone(), selected)
+        })
+        .collect();
+    Value::Object(projected)
+}
+
+/// Splits a dotted field path into its segments.
+fn split_path(field: &str) -> Vec<String> {
+    field.split('.').map(str::to_string).collect()
+}
+
+/// Follows a path of object keys and array indices into a JSON value.
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match value {
+        Value::Object(object) => object.get(segment),
+        Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Returns the text a field is compared as: strings without their quotes,
+/// everything else as compact JSON.
+fn field_text(field: &Value) -> String {
+    match field {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads a field as a number, from a JSON number or a string holding one.
+fn as_number(field: &Value) -> Option<f64> {
+    match field {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Compares a field with the value of an equality predicate.
+///
+/// Numbers compare by value, so `status=200` matches `200` and `200.0`.
+fn equals(field: &Value, expected: &str) -> bool {
+    if let Value::Number(number) = field
+        && let (Some(actual), Ok(expected)) = (number.as_f64(), expected.trim().parse::<f64>())
+    {
+        return actual == expected;
+    }
+    field_text(field) == expected
+}
+
+/// Creates the error returned for an invalid predicate.
+fn predicate_error(input: &str, reason: &str) -> Box<dyn error::Error> {
+    Box::new(io::Error::new(
+        ErrorKind::InvalidInput,
+        format!("Invalid JSON field predicate '{input}': {reason}"),
+    ))
+}
@@ -1,5 +1,8 @@
 mod display;
+mod json_field;
+mod query;
 mod search;
 
-pub use display::{display_results, print_help};
-pub use search::search;
+pub use display::{display_count, display_diff, display_file_name, display_results, print_help};
+pub(crate) use query::BooleanQuery;
+pub use search::Searcher;
@@ -0,0 +1,270 @@
+//! Parses boolean queries such as `ERROR AND payment AND NOT retry`.
+//!
+//! Terms are bare words or quoted phrases, combined with `AND`, `OR` and
+//! `NOT` (uppercase) and grouped with parentheses. `NOT` binds tightest, then
+//! `AND`, then `OR`, and terms written next to each other are joined with an
+//! implicit `AND`.
+
+use std::{
+    error,
+    io::{self, ErrorKind},
+};
+
+/// A boolean expression over the terms of a query.
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+    /// A term, by its index in [`BooleanQuery::terms`]
+    Term(usize),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluates the expression given which terms matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `matched` - Whether each term matched, by term index
+    pub fn evaluate(&self, matched: &[bool]) -> bool {
+        match self {
+            Expr::Term(index) => matched[*index],
+            Expr::Not(expr) => !expr.evaluate(matched),
+            Expr::And(left, right) => left.evaluate(matched) && right.evaluate(matched),
+            Expr::Or(left, right) => left.evaluate(matched) || right.evaluate(matched),
+        }
+    }
+
+    /// Marks the terms that appear outside of any `NOT`, which are the ones
+    /// worth highlighting.
+    ///
+    /// # Arguments
+    ///
+    /// * `negated` - Whether this expression is under an odd number of `NOT`s
+    /// * `positive` - Set to true for every positive term, by term index
+    fn mark_positive_terms(&self, negated: bool, positive: &mut [bool]) {
+        match self {
+            Expr::Term(index) => positive[*index] |= !negated,
+            Expr::Not(expr) => expr.mark_positive_terms(!negated, positive),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.mark_positive_terms(negated, positive);
+                right.mark_positive_terms(negated, positive);
+            }
+        }
+    }
+}
+
+/// A parsed boolean query.
+#[derive(Debug, PartialEq)]
+pub struct BooleanQuery {
+    pub expr: Expr,
+    /// The distinct terms of the query, in order of first appearance
+    pub terms: Vec<String>,
+}
+
+impl BooleanQuery {
+    /// Parses a boolean query.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The query text
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query is empty, has an unterminated phrase or
+    /// unbalanced parentheses, or an operator is missing an operand.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn error::Error>> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+            terms: Vec::new(),
+        };
+
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(query_error(format!("unexpected {}", token.describe())));
+        }
+
+        Ok(Self {
+            expr,
+            terms: parser.terms,
+        })
+    }
+
+    /// Returns whether each term appears outside of any `NOT`, by term index.
+    pub fn positive_terms(&self) -> Vec<bool> {
+        let mut positive = vec![false; self.terms.len()];
+        self.expr.mark_positive_terms(false, &mut positive);
+        positive
+    }
+}
+
+/// A token of a boolean query.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Term(String),
+    And,
+    Or,
+    Not,
+    OpenParen,
+    CloseParen,
+}
+
+impl Token {
+    /// Describes the token for error messages.
+    fn describe(&self) -> String {
+        match self {
+            Token::Term(term) => format!("term '{term}'"),
+            Token::And => "'AND'".to_string(),
+            Token::Or => "'OR'".to_string(),
+            Token::Not => "'NOT'".to_string(),
+            Token::OpenParen => "'('".to_string(),
+            Token::CloseParen => "')'".to_string(),
+        }
+    }
+}
+
+/// Splits a query into tokens.
+///
+/// Quoted phrases become a single term, and the operator keywords are only
+/// recognized in uppercase so that `and` can still be searched for.
+fn tokenize(input: &str) -> Result<Vec<Token>, Box<dyn error::Error>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::OpenParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::CloseParen);
+            }
+            '"' => {
+                chars.next();
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => phrase.push(c),
+                        None => return Err(query_error("unterminated quoted phrase".to_string())),
+                    }
+                }
+                if phrase.is_empty() {
+                    return Err(query_error("empty quoted phrase".to_string()));
+                }
+                tokens.push(Token::Term(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) =
+                    chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"'))
+                {
+                    word.push(c);
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(word),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a query.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    terms: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Parses `and (OR and)*`.
+    fn parse_or(&mut self) -> Result<Expr, Box<dyn error::Error>> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    /// Parses `not ([AND] not)*`, where a missing `AND` is implied.
+    fn parse_and(&mut self) -> Result<Expr, Box<dyn error::Error>> {
+        let mut expr = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Term(_) | Token::Not | Token::OpenParen) => {}
+                _ => return Ok(expr),
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+    }
+
+    /// Parses `NOT not | primary`.
+    fn parse_not(&mut self) -> Result<Expr, Box<dyn error::Error>> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    /// Parses a term or a parenthesized expression.
+    fn parse_primary(&mut self) -> Result<Expr, Box<dyn error::Error>> {
+        match self.next() {
+            Some(Token::Term(term)) => {
+                let index = match self.terms.iter().position(|known| *known == term) {
+                    Some(index) => index,
+                    None => {
+                        self.terms.push(term);
+                        self.terms.len() - 1
+                    }
+                };
+                Ok(Expr::Term(index))
+            }
+            Some(Token::OpenParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(expr),
+                    _ => Err(query_error("missing ')'".to_string())),
+                }
+            }
+            Some(token) => Err(query_error(format!(
+                "expected a term but found {}",
+                token.describe()
+            ))),
+            None => Err(query_error("expected a term at the end".to_string())),
+        }
+    }
+}
+
+/// Creates the error returned for an invalid query.
+fn query_error(reason: String) -> Box<dyn error::Error> {
+    Box::new(io::Error::new(
+        ErrorKind::InvalidInput,
+        format!("Invalid query: {reason}"),
+    ))
+}
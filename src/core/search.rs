@@ -1,197 +1,992 @@
 //! Core search functionality for finding pattern matches in text.
 
-use crate::models::SearchResult;
+use super::{
+    json_field::FieldPredicate,
+    query::{BooleanQuery, Expr},
+};
+use crate::models::{
+    CaseMode, LineEnding, MatchBoundary, PatternMode, SearchOptions, SearchResult, TableColumn,
+};
+use crate::utils::{FoldedText, Folding, table_fields, table_rows, unquote_field};
+use aho_corasick::AhoCorasick;
 use regex::Regex;
 use std::{
-    collections::HashSet,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
     error,
     io::{self, ErrorKind},
+    iter,
+    sync::LazyLock,
 };
 
-/// Searches content for matches of a query pattern, with context support.
-///
-/// # Arguments
-///
-/// * `query` - The pattern to search for (may be plain text or regex)
-/// * `contents` - The text content to search within
-/// * `context` - Type of context to display ("before", "after", "context", or "")
-/// * `content_count` - Number of context lines to include
-/// * `ignore_case` - Whether to perform case-insensitive matching
-///
-/// # Returns
-///
-/// * `Result<Vec<SearchResult>, Error>` - A vector of search results or an error
-///
-/// # Errors
+/// The compiled patterns of a search, matched against each line.
 ///
-/// Returns an error if the query contains an invalid regex pattern.
-pub fn search(
-    query: &str,
-    contents: &str,
-    context: &str,
-    content_count: Option<usize>, // Option because tests test for non-existing content_count
+/// Literal patterns are searched for in a single pass over the line with an
+/// Aho-Corasick automaton, so many patterns cost little more than one.
+struct Matcher {
+    patterns: Vec<Pattern>,
+    /// Automata over the literal patterns, one for case-sensitive and one for
+    /// case-insensitive patterns
+    automata: Vec<LiteralAutomaton>,
+    /// Whether an empty literal pattern makes every line match
+    matches_every_line: bool,
+    /// A boolean query deciding which lines match, whose terms are the
+    /// patterns
+    expression: Option<Expression>,
+    boundary: MatchBoundary,
+    /// Normalization applied to lines before matching, without case folding
+    folding: Folding,
+    /// The maximum edit distance of fuzzy patterns
+    max_distance: usize,
+}
+
+/// A single compiled pattern.
+enum Pattern {
+    /// A regular expression, with any word or line boundary built in
+    Regex { regex: Regex, source: String },
+    /// A literal pattern, searched for by one of the automata
+    Literal { source: String },
+    /// A literal pattern matched approximately, allowing some edits
+    Fuzzy {
+        source: String,
+        /// The folded pattern
+        chars: Vec<char>,
+        ignore_case: bool,
+    },
+}
+
+/// An automaton searching for several literal patterns at once.
+struct LiteralAutomaton {
+    automaton: AhoCorasick,
+    /// Index in `Matcher::patterns` of each automaton pattern
+    pattern_indices: Vec<usize>,
+    /// Whether lines are case folded before being searched
     ignore_case: bool,
-) -> Result<Vec<SearchResult>, Box<dyn error::Error>> {
-    let lines: Vec<&str> = contents.lines().collect();
-    let mut line_numbers_to_include = HashSet::new();
+}
 
-    // Check if the query looks like a regex pattern
-    let regex_indicators = [
-        '*', '+', '?', '.', '\\', '[', ']', '(', ')', '{', '}', '^', '$',
-    ];
-    let might_be_regex = query.chars().any(|c| regex_indicators.contains(&c));
+/// The expression of a boolean query.
+struct Expression {
+    expr: Expr,
+    /// Whether each term appears outside of any `NOT`, and so is highlighted
+    positive_terms: Vec<bool>,
+}
 
-    if might_be_regex {
-        // Try to compile the regex pattern
-        let regex_result = if ignore_case {
-            regex::RegexBuilder::new(query)
-                .case_insensitive(true)
-                .build()
-        } else {
-            Regex::new(query)
+/// A match of a single pattern, in both the folded and the original line.
+struct PatternMatch {
+    pattern_index: usize,
+    folded_span: (usize, usize),
+    original_span: (usize, usize),
+}
+
+impl Matcher {
+    /// Compiles the query and extra patterns of the search options into a
+    /// matcher.
+    ///
+    /// The query is split on `|` unless it is a regex. Extra patterns are
+    /// never split, so they may contain a literal `|`. In auto mode each
+    /// extra pattern is checked for regex characters on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The search options holding the patterns and matching modes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any pattern is an invalid regex.
+    fn new(options: &SearchOptions) -> Result<Self, Box<dyn error::Error>> {
+        let query = options.query.as_str();
+        // Fuzzy patterns are always literal
+        let pattern_mode = match options.fuzzy {
+            Some(_) => PatternMode::Literal,
+            None => options.pattern_mode,
         };
 
-        // Handle regex compilation errors
-        let regex = match regex_result {
-            Ok(r) => r,
-            Err(e) => {
-                return Err(Box::new(io::Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("Invalid regex pattern: '{}'", e),
-                )));
-            }
+        // Normalization applies to both regex and literal patterns, case
+        // folding only to literal patterns that ignore case
+        let folding = Folding {
+            ignore_case: false,
+            normalization: options.normalization,
+            ignore_accents: options.ignore_accents,
         };
 
-        // Find matches using regex
-        for (line_number, line_content) in lines.iter().enumerate() {
-            if regex.is_match(line_content) {
-                // Add matched line
-                line_numbers_to_include.insert(line_number);
+        let mut sources = Vec::new();
+        let boolean_query = options
+            .boolean_query
+            .as_deref()
+            .map(BooleanQuery::parse)
+            .transpose()?;
 
-                // Handle context based on the flag
-                match context {
-                    "before" => {
-                        if let Some(before_count) = content_count {
-                            let first_line = line_number.saturating_sub(before_count);
-                            for i in first_line..line_number {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
-                    }
-                    "after" => {
-                        if let Some(after_count) = content_count {
-                            let next_line = line_number + 1;
-                            let last_line = (line_number + after_count).min(lines.len() - 1);
-                            for i in next_line..=last_line {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
-                    }
-                    "context" => {
-                        if let Some(context_count) = content_count {
-                            let first_line = line_number.saturating_sub(context_count);
-                            let last_line = (line_number + context_count).min(lines.len() - 1);
-                            for i in first_line..=last_line {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
+        if let Some(boolean_query) = &boolean_query {
+            // A boolean query replaces the query and extra patterns
+            for term in &boolean_query.terms {
+                sources.push((term.as_str(), is_regex(pattern_mode, term)));
+            }
+        } else if !query.is_empty() || options.patterns.is_empty() {
+            // The query may be left empty when all patterns are given separately
+            if is_regex(pattern_mode, query) {
+                sources.push((query, true));
+            } else {
+                // Split query into patterns by pipe character
+                for pattern in query.split('|') {
+                    let pattern = if query.contains('|') {
+                        pattern.trim()
+                    } else {
+                        pattern
+                    };
+                    sources.push((pattern, false));
+                }
+            }
+        }
+
+        if boolean_query.is_none() {
+            for pattern in &options.patterns {
+                sources.push((pattern, is_regex(pattern_mode, pattern)));
+            }
+        }
+
+        let mut patterns = Vec::with_capacity(sources.len());
+        // Folded literal texts and pattern indices, for case-sensitive and
+        // case-insensitive patterns
+        let mut literals: [(Vec<String>, Vec<usize>); 2] = Default::default();
+        let mut matches_every_line = false;
+
+        for (index, (source, is_regex)) in sources.into_iter().enumerate() {
+            if is_regex {
+                patterns.push(Pattern::regex(source, options, folding)?);
+                continue;
+            }
+
+            let ignore_case = ignores_case(options.case_mode, source, false);
+            let text = folding.with_ignore_case(ignore_case).apply(source);
+
+            // An empty pattern matches every line but has nothing to highlight
+            if text.is_empty() {
+                matches_every_line = true;
+            } else if options.fuzzy.is_some() {
+                patterns.push(Pattern::Fuzzy {
+                    source: source.to_string(),
+                    chars: text.chars().collect(),
+                    ignore_case,
+                });
+                continue;
+            } else {
+                let (texts, indices) = &mut literals[usize::from(ignore_case)];
+                texts.push(text);
+                indices.push(index);
+            }
+            patterns.push(Pattern::Literal {
+                source: source.to_string(),
+            });
+        }
+
+        let mut automata = Vec::new();
+        for (ignore_case, (texts, pattern_indices)) in [false, true].into_iter().zip(literals) {
+            if !texts.is_empty() {
+                automata.push(LiteralAutomaton {
+                    automaton: AhoCorasick::new(&texts)?,
+                    pattern_indices,
+                    ignore_case,
+                });
+            }
+        }
+
+        Ok(Matcher {
+            patterns,
+            automata,
+            matches_every_line,
+            expression: boolean_query.map(|boolean_query| Expression {
+                positive_terms: boolean_query.positive_terms(),
+                expr: boolean_query.expr,
+            }),
+            boundary: options.boundary,
+            folding,
+            max_distance: options.fuzzy.unwrap_or(0),
+        })
+    }
+
+    /// Returns true if the line contains at least one match.
+    fn is_match(&self, line: &str) -> bool {
+        if let Some(expression) = &self.expression {
+            let mut matched = vec![false; self.patterns.len()];
+            for (pattern_index, _) in self.pattern_matches(line) {
+                matched[pattern_index] = true;
+            }
+            return expression.expr.evaluate(&matched);
+        }
+
+        // Literal matches need their boundaries checked, which needs the spans
+        if self.boundary != MatchBoundary::None {
+            return !self.find_matches(line).1.is_empty();
+        }
+
+        if self.matches_every_line {
+            return true;
+        }
+
+        let exact = FoldedText::new(line, self.folding);
+
+        self.automata.iter().any(|automaton| {
+            if automaton.ignore_case {
+                let folded = FoldedText::new(line, self.folding.with_ignore_case(true));
+                automaton.automaton.is_match(folded.as_str())
+            } else {
+                automaton.automaton.is_match(exact.as_str())
+            }
+        }) || self.patterns.iter().any(|pattern| match pattern {
+            Pattern::Regex { regex, .. } => regex.is_match(exact.as_str()),
+            Pattern::Literal { .. } => false,
+            Pattern::Fuzzy {
+                chars, ignore_case, ..
+            } => self.fuzzy_match(line, chars, *ignore_case).is_some(),
+        })
+    }
+
+    /// Finds the patterns that matched in the line and the byte ranges of
+    /// every match.
+    ///
+    /// Each matching pattern is recorded by its index. Overlapping spans
+    /// from different patterns are merged, and the spans are sorted by start
+    /// position. Matching happens on the folded line, but the spans always
+    /// refer to the original line.
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<usize>, Vec<(usize, usize)>)` - The indices of the matching patterns
+    ///   and the `(start, end)` byte offsets of the matches
+    fn find_matches(&self, line: &str) -> (Vec<usize>, Vec<(usize, usize)>) {
+        self.attribute_matches(&self.pattern_matches(line))
+    }
+
+    /// Collects the matching patterns and merged spans of the matches of a
+    /// line, as returned by [`Matcher::find_matches`].
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - The pattern index and span of each match, sorted by pattern
+    fn attribute_matches(
+        &self,
+        matches: &[(usize, (usize, usize))],
+    ) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let mut matching_patterns = Vec::new();
+        let mut spans = Vec::new();
+        // Pattern index of the last recorded matching pattern
+        let mut recorded = None;
+
+        for &(pattern_index, span) in matches {
+            // Terms of a boolean query that are negated aren't highlighted
+            if self
+                .expression
+                .as_ref()
+                .is_some_and(|expression| !expression.positive_terms[pattern_index])
+            {
+                continue;
+            }
+
+            if recorded != Some(pattern_index) {
+                matching_patterns.push(pattern_index);
+                recorded = Some(pattern_index);
+            }
+            spans.push(span);
+        }
+
+        (matching_patterns, merge_spans(spans))
+    }
+
+    /// Finds every match of every pattern in the line.
+    ///
+    /// Occurrences of the same pattern don't overlap, and literal matches
+    /// outside the required boundary are dropped.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(usize, (usize, usize))>` - The pattern index and the `(start, end)`
+    ///   byte offsets in the original line of each match, sorted by pattern
+    fn pattern_matches(&self, line: &str) -> Vec<(usize, (usize, usize))> {
+        let exact = FoldedText::new(line, self.folding);
+        let mut matches = Vec::new();
+
+        for automaton in &self.automata {
+            let folded;
+            let line_to_check = if automaton.ignore_case {
+                folded = FoldedText::new(line, self.folding.with_ignore_case(true));
+                &folded
+            } else {
+                &exact
+            };
+
+            // Overlapping search finds every occurrence of every pattern, even
+            // where patterns overlap each other
+            for m in automaton
+                .automaton
+                .find_overlapping_iter(line_to_check.as_str())
+            {
+                matches.push(PatternMatch {
+                    pattern_index: automaton.pattern_indices[m.pattern().as_usize()],
+                    folded_span: (m.start(), m.end()),
+                    // Spans found in the folded line are mapped back to the
+                    // original line so they can be highlighted there
+                    original_span: line_to_check.original_span(m.start(), m.end()),
+                });
+            }
+        }
+
+        for (pattern_index, pattern) in self.patterns.iter().enumerate() {
+            match pattern {
+                Pattern::Regex { regex, .. } => {
+                    for m in regex.find_iter(exact.as_str()) {
+                        matches.push(PatternMatch {
+                            pattern_index,
+                            folded_span: (m.start(), m.end()),
+                            original_span: exact.original_span(m.start(), m.end()),
+                        });
                     }
-                    _ => {
-                        // Default case with no context
-                        // Matched line number already included
+                }
+                // Only the closest match of a fuzzy pattern is kept
+                Pattern::Fuzzy {
+                    chars, ignore_case, ..
+                } => {
+                    if let Some((_, span)) = self.fuzzy_match(line, chars, *ignore_case) {
+                        matches.push(PatternMatch {
+                            pattern_index,
+                            folded_span: span,
+                            original_span: span,
+                        });
                     }
                 }
+                Pattern::Literal { .. } => {}
             }
         }
 
-        // Collect matches for the regex to use in highlighting
-        let mut results = Vec::new();
-        let mut line_numbers: Vec<usize> = line_numbers_to_include.into_iter().collect();
-        line_numbers.sort();
+        // Group the matches by pattern, then keep the leftmost of any
+        // overlapping occurrences of the same pattern
+        matches.sort_by_key(|m| (m.pattern_index, m.folded_span.0));
 
-        for &line_number in &line_numbers {
-            let line_content = lines[line_number].to_string();
-            // We'll store the regex pattern to use for highlighting
-            results.push(SearchResult::new(
-                line_number,
-                line_content,
-                vec![query.to_string()], // Store the regex pattern as a plain string
-            ));
-        }
-
-        Ok(results)
-    } else {
-        // Split query into patterns by pipe character
-        let patterns = if query.contains('|') {
-            query
-                .split('|')
-                .map(|pattern| pattern.trim().to_string())
-                .collect::<Vec<String>>()
-        } else {
-            vec![query.to_string()]
+        let mut kept = Vec::new();
+        // Pattern index and folded end of the last kept match
+        let mut previous: Option<(usize, usize)> = None;
+
+        for m in matches {
+            let (start, end) = m.original_span;
+            if previous.is_some_and(|(index, prev_end)| {
+                index == m.pattern_index && m.folded_span.0 < prev_end
+            }) {
+                continue;
+            }
+            previous = Some((m.pattern_index, m.folded_span.1));
+
+            if !matches!(self.patterns[m.pattern_index], Pattern::Regex { .. })
+                && !within_boundary(line, start, end, self.boundary)
+            {
+                continue;
+            }
+            kept.push((m.pattern_index, (start, end)));
+        }
+
+        kept
+    }
+
+    /// Replaces every match in the line using a replacement template.
+    ///
+    /// Regex matches expand `$1` and `${name}` to their capture groups. For
+    /// other patterns only `$0`, the whole match, is available. Where matches
+    /// of different patterns overlap, the leftmost one is replaced, and the
+    /// longest of those starting at the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The original line
+    /// * `template` - The replacement text, with `$$` for a literal `$`
+    ///
+    /// # Returns
+    ///
+    /// * `(String, Vec<(usize, usize)>, Vec<(usize, usize)>)` - The replaced
+    ///   line, the `(start, end)` byte offsets of every replacement in it, and
+    ///   the offsets of the replaced match in the original line
+    fn replace(&self, line: &str, template: &str) -> (String, Spans, Spans) {
+        // Captures the whole match as group 0 and nothing else
+        static WHOLE_MATCH: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?s).*").unwrap());
+
+        let exact = FoldedText::new(line, self.folding);
+        let is_positive = |pattern_index: usize| {
+            self.expression
+                .as_ref()
+                .is_none_or(|expression| expression.positive_terms[pattern_index])
         };
+        // Span in the original line and replacement text of every match
+        let mut replacements = Vec::new();
 
-        // Convert patterns in query to lowercase if ignore_case is true
-        let patterns_to_use = if ignore_case {
-            patterns
-                .iter()
-                .map(|pattern| pattern.to_lowercase())
-                .collect::<Vec<String>>()
+        for (pattern_index, pattern) in self.patterns.iter().enumerate() {
+            if let Pattern::Regex { regex, .. } = pattern
+                && is_positive(pattern_index)
+            {
+                for captures in regex.captures_iter(exact.as_str()) {
+                    let m = captures.get(0).unwrap();
+                    let mut replacement = String::new();
+                    captures.expand(template, &mut replacement);
+                    replacements.push((exact.original_span(m.start(), m.end()), replacement));
+                }
+            }
+        }
+        for (pattern_index, (start, end)) in self.pattern_matches(line) {
+            if !matches!(self.patterns[pattern_index], Pattern::Regex { .. })
+                && is_positive(pattern_index)
+            {
+                let mut replacement = String::new();
+                if let Some(captures) = WHOLE_MATCH.captures(&line[start..end]) {
+                    captures.expand(template, &mut replacement);
+                }
+                replacements.push(((start, end), replacement));
+            }
+        }
+
+        replacements.sort_by_key(|&((start, end), _)| (start, std::cmp::Reverse(end)));
+
+        let mut replaced = String::with_capacity(line.len());
+        let mut spans = Vec::new();
+        let mut original_spans = Vec::new();
+        // End of the last replaced match in the original line
+        let mut copied = 0;
+
+        for ((start, end), replacement) in replacements {
+            if start < copied || (start == end && start == copied && !spans.is_empty()) {
+                continue;
+            }
+            replaced.push_str(&line[copied..start]);
+            spans.push((replaced.len(), replaced.len() + replacement.len()));
+            original_spans.push((start, end));
+            replaced.push_str(&replacement);
+            copied = end;
+        }
+        replaced.push_str(&line[copied..]);
+
+        (replaced, spans, original_spans)
+    }
+
+    /// Finds the substring of the line closest to a fuzzy pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The original line
+    /// * `chars` - The folded pattern
+    /// * `ignore_case` - Whether the line is case folded before matching
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(usize, (usize, usize))>` - The edit distance and the `(start, end)`
+    ///   byte offsets in the original line, or `None` if nothing is close enough
+    fn fuzzy_match(
+        &self,
+        line: &str,
+        chars: &[char],
+        ignore_case: bool,
+    ) -> Option<(usize, (usize, usize))> {
+        let folded = FoldedText::new(line, self.folding.with_ignore_case(ignore_case));
+        let (distance, (start, end)) = closest_substring(chars, folded.as_str())?;
+        (distance <= self.max_distance).then(|| (distance, folded.original_span(start, end)))
+    }
+
+    /// Returns the edit distance of each match of the line.
+    ///
+    /// Where the matches of several fuzzy patterns were merged into one span,
+    /// the span gets the smallest of their distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The original line
+    /// * `spans` - The `(start, end)` byte offsets of the matches in the line
+    fn fuzzy_distances(&self, line: &str, spans: &[(usize, usize)]) -> Vec<usize> {
+        let fuzzy_matches: Vec<(usize, (usize, usize))> = self
+            .patterns
+            .iter()
+            .filter_map(|pattern| match pattern {
+                Pattern::Fuzzy {
+                    chars, ignore_case, ..
+                } => self.fuzzy_match(line, chars, *ignore_case),
+                _ => None,
+            })
+            .collect();
+
+        spans
+            .iter()
+            .map(|&(start, end)| {
+                fuzzy_matches
+                    .iter()
+                    .filter(|(_, (match_start, match_end))| {
+                        start <= *match_start && *match_end <= end
+                    })
+                    .map(|&(distance, _)| distance)
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Compiles every pattern into a regex for matching against a whole
+    /// content rather than a single line.
+    ///
+    /// Literal patterns are escaped, and `^` and `$` match at line breaks, or
+    /// at NUL bytes when searching null data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a regex pattern is invalid.
+    fn multiline_regexes(
+        &self,
+        options: &SearchOptions,
+    ) -> Result<Vec<Regex>, Box<dyn error::Error>> {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                let source = pattern.source();
+                let is_regex = matches!(pattern, Pattern::Regex { .. });
+                let normalized = self.folding.apply(source);
+                let normalized = if is_regex {
+                    normalized
+                } else {
+                    regex::escape(&normalized)
+                };
+                let pattern = match options.boundary {
+                    MatchBoundary::None => normalized,
+                    MatchBoundary::Word => format!(r"\b(?:{normalized})\b"),
+                    MatchBoundary::Line => format!("^(?:{normalized})$"),
+                };
+
+                let mut builder = regex::RegexBuilder::new(&pattern);
+                builder
+                    .case_insensitive(ignores_case(options.case_mode, source, is_regex))
+                    .multi_line(true)
+                    .dot_matches_new_line(options.multiline_dotall);
+                if options.null_data {
+                    builder.line_terminator(b'\0');
+                } else {
+                    builder.crlf(options.crlf);
+                }
+                builder.build().map_err(|e| {
+                    Box::new(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid regex pattern: '{}'", e),
+                    )) as Box<dyn error::Error>
+                })
+            })
+            .collect()
+    }
+
+    /// Returns every pattern as it was given, in order.
+    fn sources(&self) -> impl Iterator<Item = &str> {
+        self.patterns.iter().map(Pattern::source)
+    }
+}
+
+impl Pattern {
+    /// Compiles a regex pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is an invalid regex.
+    fn regex(
+        source: &str,
+        options: &SearchOptions,
+        folding: Folding,
+    ) -> Result<Self, Box<dyn error::Error>> {
+        // Anchor the whole expression for word and line matching
+        let normalized = folding.apply(source);
+        let pattern = match options.boundary {
+            MatchBoundary::None => normalized,
+            MatchBoundary::Word => format!(r"\b(?:{normalized})\b"),
+            MatchBoundary::Line => format!("^(?:{normalized})$"),
+        };
+
+        // Try to compile the regex pattern
+        let regex_result = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(ignores_case(options.case_mode, source, true))
+            .crlf(options.crlf)
+            .build();
+
+        // Handle regex compilation errors
+        match regex_result {
+            Ok(regex) => Ok(Pattern::Regex {
+                regex,
+                source: source.to_string(),
+            }),
+            Err(e) => Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid regex pattern: '{}'", e),
+            ))),
+        }
+    }
+
+    /// Returns the pattern as it was given, before any folding.
+    fn source(&self) -> &str {
+        match self {
+            Pattern::Regex { source, .. }
+            | Pattern::Literal { source, .. }
+            | Pattern::Fuzzy { source, .. } => source,
+        }
+    }
+}
+
+/// A compiled search, created from [`SearchOptions::build`].
+///
+/// The query is compiled once and can be used to search any number of files.
+pub struct Searcher {
+    options: SearchOptions,
+    matcher: Matcher,
+    /// Patterns that must each occur somewhere in a file
+    all_of: Vec<Matcher>,
+    /// Patterns that must not occur anywhere in a file
+    none_of: Vec<Matcher>,
+    /// In multiline mode, each pattern compiled to match against the whole
+    /// content, in pattern order
+    multiline: Option<Vec<Regex>>,
+    /// What separates the records of the content, when they aren't lines
+    record_separator: Option<Regex>,
+    /// Conditions on the fields of lines holding JSON objects, which a line
+    /// must all satisfy besides matching the query
+    json_fields: Vec<FieldPredicate>,
+}
+
+/// The `(start, end)` byte offsets of matches on a line.
+type Spans = Vec<(usize, usize)>;
+
+/// The pattern index and the `(start, end)` byte offsets of each match on a
+/// line.
+type LineMatches = Vec<(usize, (usize, usize))>;
+
+/// The matches of a multiline search, split into the lines they cover.
+struct MultilineMatches {
+    /// The first and last line number of each match, in order
+    ranges: Vec<(usize, usize)>,
+    /// The pattern index and the part of the match on the line, for every
+    /// line a match covers
+    lines: HashMap<usize, LineMatches>,
+}
+
+/// The part of a line that is matched.
+#[derive(Clone)]
+struct SearchedPart<'a> {
+    /// The text to match, with the `""` of a quoted field unescaped
+    text: Cow<'a, str>,
+    /// Byte offset of the part in the line
+    start: usize,
+    /// Whether the part is a quoted field, whose quotes are doubled in the line
+    quoted: bool,
+}
+
+impl<'a> SearchedPart<'a> {
+    /// Creates the part covering a whole line.
+    fn whole(line: &'a str) -> Self {
+        Self {
+            text: Cow::Borrowed(line),
+            start: 0,
+            quoted: false,
+        }
+    }
+
+    /// Creates the part for a field of a table row.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The text of the row
+    /// * `span` - The `(start, end)` byte offsets of the field in the row, as
+    ///   returned by [`table_fields`]
+    fn field(row: &'a str, (start, end): (usize, usize)) -> Self {
+        // Only a quoted field has its text right after a quote
+        let quoted = start > 0 && row.as_bytes()[start - 1] == b'"';
+        let field = &row[start..end];
+        let text = if quoted && field.contains("\"\"") {
+            Cow::Owned(unquote_field(field))
         } else {
-            patterns
+            Cow::Borrowed(field)
         };
+        Self {
+            text,
+            start,
+            quoted,
+        }
+    }
 
-        for (line_number, line_content) in lines.iter().enumerate() {
-            // Handle case sensitivity for the line content
-            let line_to_check = if ignore_case {
-                line_content.to_lowercase()
+    /// Returns text of this part as it is written in the line.
+    fn escape<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if self.quoted {
+            Cow::Owned(text.replace('"', "\"\""))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Moves spans in the text of this part, or in a replacement of it, to
+    /// where they are in the line.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The unescaped text the spans refer to
+    /// * `spans` - The `(start, end)` byte offsets of the spans in the text
+    fn line_spans(&self, text: &str, spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+        // Every quote before an offset takes two bytes in the line
+        let in_line = |offset: usize| {
+            let quotes = if self.quoted {
+                text[..offset].matches('"').count()
             } else {
-                line_content.to_string()
+                0
             };
+            self.start + offset + quotes
+        };
+        spans
+            .into_iter()
+            .map(|(start, end)| (in_line(start), in_line(end)))
+            .collect()
+    }
+}
 
-            // Check if the line contains any of the patterns
-            let contains_match = patterns_to_use
-                .iter()
-                .any(|pattern| line_to_check.contains(pattern));
+impl Searcher {
+    /// Compiles search options into a searcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The search options to compile
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query, a file-level pattern or the record
+    /// separator contains an invalid regex pattern, or a JSON field predicate
+    /// can't be parsed.
+    pub fn new(options: SearchOptions) -> Result<Self, Box<dyn error::Error>> {
+        let matcher = Matcher::new(&options)?;
 
-            if contains_match {
-                // Include the matched line number
-                line_numbers_to_include.insert(line_number);
+        if options.near.is_some() && matcher.patterns.len() < 2 {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Proximity search needs at least two patterns",
+            )));
+        }
 
-                // Handle context based on the flag
-                match context {
-                    "before" => {
-                        if let Some(before_count) = content_count {
-                            let first_line = line_number.saturating_sub(before_count);
-                            for i in first_line..line_number {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
-                    }
-                    "after" => {
-                        if let Some(after_count) = content_count {
-                            let next_line = line_number + 1;
-                            let last_line = (line_number + after_count).min(lines.len() - 1);
-                            for i in next_line..=last_line {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
-                    }
-                    "context" => {
-                        if let Some(context_count) = content_count {
-                            let first_line = line_number.saturating_sub(context_count);
-                            let last_line = (line_number + context_count).min(lines.len() - 1);
-                            for i in first_line..=last_line {
-                                line_numbers_to_include.insert(i);
-                            }
-                        }
-                    }
-                    _ => {
-                        // Default case with no context
-                        // Matched line number already included
-                    }
+        if options.multiline
+            && (options.near.is_some()
+                || options.boolean_query.is_some()
+                || options.fuzzy.is_some()
+                || options.replace.is_some())
+        {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Multiline matching can't be combined with a boolean query, proximity or fuzzy \
+                 search, or replacement",
+            )));
+        }
+        if !options.json_fields.is_empty() && (options.multiline || options.near.is_some()) {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "JSON field predicates can't be combined with multiline matching or proximity \
+                 search",
+            )));
+        }
+        if options.table_format.is_some()
+            && (options.multiline || options.null_data || options.record_separator.is_some())
+        {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "A CSV or TSV table can't be combined with multiline matching, null data or a \
+                 record separator",
+            )));
+        }
+        if options.table_column.is_some() && options.table_format.is_none() {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "A table column needs a CSV or TSV table format",
+            )));
+        }
+
+        let json_fields = options
+            .json_fields
+            .iter()
+            .map(|predicate| FieldPredicate::parse(predicate))
+            .collect::<Result<_, _>>()?;
+
+        let multiline = options
+            .multiline
+            .then(|| matcher.multiline_regexes(&options))
+            .transpose()?;
+
+        // File-level patterns are matched exactly as a single literal or
+        // regex each, never split on `|` or matched fuzzily, with the same
+        // case and normalization handling as the query
+        let file_matcher = |pattern: &String| {
+            Matcher::new(&SearchOptions {
+                query: String::new(),
+                patterns: vec![pattern.clone()],
+                boolean_query: None,
+                fuzzy: None,
+                ..options.clone()
+            })
+        };
+        let all_of = options
+            .all_of
+            .iter()
+            .map(file_matcher)
+            .collect::<Result<_, _>>()?;
+        let none_of = options
+            .none_of
+            .iter()
+            .map(file_matcher)
+            .collect::<Result<_, _>>()?;
+
+        let record_separator = options
+            .record_separator
+            .as_deref()
+            .map(|separator| {
+                Regex::new(separator).map_err(|e| {
+                    io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid record separator: '{}'", e),
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            options,
+            matcher,
+            all_of,
+            none_of,
+            multiline,
+            record_separator,
+            json_fields,
+        })
+    }
+
+    /// Returns every pattern of the search as it was given, in order.
+    ///
+    /// A literal query is split into its `|`-separated patterns.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.matcher.sources()
+    }
+
+    /// Returns the options this searcher was built from.
+    pub fn options(&self) -> &SearchOptions {
+        &self.options
+    }
+
+    /// Splits content into the lines that are searched.
+    ///
+    /// Lines end with `\n` or `\r\n`, with NUL when searching null data, or
+    /// with a match of the record separator, and the terminators are left
+    /// out. The lines of a table are its rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content to split
+    pub fn records<'a>(&self, contents: &'a str) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        if let Some(format) = self.options.table_format {
+            Box::new(
+                table_rows(contents, format.delimiter())
+                    .into_iter()
+                    .map(|(start, end)| &contents[start..end]),
+            )
+        } else if let Some(separator) = &self.record_separator {
+            Box::new(
+                separated_records(separator, contents)
+                    .into_iter()
+                    .map(|(start, end)| &contents[start..end]),
+            )
+        } else if self.options.null_data {
+            Box::new(contents.split_terminator('\0'))
+        } else {
+            Box::new(contents.lines())
+        }
+    }
+
+    /// Searches content for matching lines, with context support.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content to search within
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<SearchResult>` - The matching and context lines, in line order
+    pub fn search(&self, contents: &str) -> Vec<SearchResult> {
+        self.search_with_limit(contents, None)
+    }
+
+    /// Searches content for matching lines, stopping after a maximum number
+    /// of matching lines.
+    ///
+    /// The limit is combined with the configured max count, the smaller one
+    /// wins. Context lines after the last allowed match are still included,
+    /// marked as context even if they contain a match themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content to search within
+    /// * `limit` - Maximum number of matching lines to return, or `None` for no extra limit
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<SearchResult>` - The matching and context lines, in line order
+    pub fn search_with_limit(&self, contents: &str, limit: Option<usize>) -> Vec<SearchResult> {
+        let max_count = self.max_count(limit);
+        let context = self.options.context;
+        let lines: Vec<&str> = self.records(contents).collect();
+        let parts: Vec<Option<SearchedPart>> = self.searched_parts(contents).collect();
+        // The text matched on each line, leaving out what isn't searched
+        let texts: Vec<&str> = parts
+            .iter()
+            .map(|part| part.as_ref().map_or("", |part| &part.text))
+            .collect();
+        let line_starts = self.record_starts(contents);
+        let mut line_numbers_to_include = HashSet::new();
+        let mut matched_line_numbers = HashSet::new();
+        let multiline = self.multiline_matches(contents, &lines, &line_starts);
+
+        // Each group is a single selected line, a whole window in proximity
+        // mode, or all the lines a match covers in multiline mode
+        let groups: Box<dyn Iterator<Item = (usize, usize)>> = match (&multiline, self.options.near)
+        {
+            (Some(multiline), _) if self.options.invert => Box::new(
+                (0..lines.len())
+                    .filter(|line_number| !multiline.lines.contains_key(line_number))
+                    .map(|line_number| (line_number, line_number)),
+            ),
+            (Some(multiline), _) => Box::new(multiline.ranges.iter().copied()),
+            (None, Some(distance)) => Box::new(self.near_windows(&texts, distance).into_iter()),
+            (None, None) => Box::new(
+                (0..lines.len())
+                    .filter(|&line_number| {
+                        parts[line_number]
+                            .as_ref()
+                            .is_some_and(|part| self.is_selected(&part.text))
+                    })
+                    .map(|line_number| (line_number, line_number)),
+            ),
+        };
+
+        'groups: for (first, last) in groups {
+            for (offset, text) in texts[first..=last].iter().enumerate() {
+                let line_number = first + offset;
+
+                // Stop early once the maximum number of matching lines is reached
+                if max_count.is_some_and(|max| matched_line_numbers.len() >= max) {
+                    break 'groups;
+                }
+
+                // Inside a window, only the lines where a highlighted pattern
+                // occurs are matches and the lines between them are context
+                line_numbers_to_include.insert(line_number);
+                if self.options.near.is_none() || !self.matcher.find_matches(text).1.is_empty() {
+                    matched_line_numbers.insert(line_number);
                 }
             }
+
+            // Include the context lines around the match
+            let first_line = first.saturating_sub(context.before);
+            let last_line = last.saturating_add(context.after).min(lines.len() - 1);
+            for i in first_line..=last_line {
+                line_numbers_to_include.insert(i);
+            }
+        }
+
+        // The header row of a table is always shown ahead of its matches
+        if self.options.table_format.is_some() && !matched_line_numbers.is_empty() {
+            line_numbers_to_include.insert(0);
         }
 
         // Then collect the results in order
@@ -200,26 +995,637 @@ pub fn search(
         line_numbers.sort();
 
         for &line_number in &line_numbers {
-            // Store the original matching patterns for this line to use in highlighting
-            let matching_patterns: Vec<String> = patterns_to_use
+            let line_content = lines[line_number].to_string();
+            let line_end = line_starts
+                .get(line_number + 1)
+                .copied()
+                .unwrap_or(contents.len());
+            let line_ending = LineEnding::from_terminator(
+                &contents[line_starts[line_number] + line_content.len()..line_end],
+            );
+
+            if matched_line_numbers.contains(&line_number) {
+                // Only part of the line may have been matched, whose spans are
+                // moved to where the part is in the line
+                let part = parts[line_number]
+                    .clone()
+                    .unwrap_or_else(|| SearchedPart::whole(lines[line_number]));
+                let text = part.text.as_ref();
+
+                // Store the matches for this line to use in highlighting and stats
+                let (matching_patterns, match_spans) = match &multiline {
+                    Some(multiline) if !self.options.invert => self
+                        .matcher
+                        .attribute_matches(&multiline.lines[&line_number]),
+                    _ => self.line_matches(text),
+                };
+                // Show the line with its matches replaced, highlighting the
+                // replacements, and keep where the replaced matches were
+                let (line_content, match_spans, original_spans) = match &self.options.replace {
+                    Some(template) if !self.options.invert => {
+                        let (replaced, spans, original_spans) =
+                            self.matcher.replace(text, template);
+                        let part_end = part.start + part.escape(text).len();
+                        let line_content = format!(
+                            "{}{}{}",
+                            &line_content[..part.start],
+                            part.escape(&replaced),
+                            &line_content[part_end..]
+                        );
+                        (
+                            line_content,
+                            part.line_spans(&replaced, spans),
+                            original_spans,
+                        )
+                    }
+                    _ => (
+                        line_content,
+                        part.line_spans(text, match_spans.clone()),
+                        match_spans,
+                    ),
+                };
+                let edit_distances = match self.options.fuzzy {
+                    Some(_) if !self.options.invert => {
+                        self.matcher.fuzzy_distances(text, &original_spans)
+                    }
+                    _ => Vec::new(),
+                };
+                let mut result =
+                    SearchResult::new(line_number, line_content, matching_patterns, match_spans)
+                        .with_edit_distances(edit_distances)
+                        .with_byte_offset(line_starts[line_number])
+                        .with_line_ending(line_ending);
+                if self.options.replace.is_some() && !self.options.invert {
+                    let original_spans = part.line_spans(text, original_spans);
+                    result = result.with_original(lines[line_number].to_string(), original_spans);
+                }
+                results.push(result);
+            } else {
+                results.push(
+                    SearchResult::context(line_number, line_content)
+                        .with_byte_offset(line_starts[line_number])
+                        .with_line_ending(line_ending),
+                );
+            }
+        }
+
+        results
+    }
+
+    /// Counts the matching lines and the individual matches in the content.
+    ///
+    /// Repeated matches on the same line are counted individually. With
+    /// inverted matching every selected line counts as one match.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content to search within
+    ///
+    /// # Returns
+    ///
+    /// * `(usize, usize)` - The number of matching lines and the number of individual matches
+    pub fn count_matches(&self, contents: &str) -> (usize, usize) {
+        self.count_matches_with_limit(contents, None)
+    }
+
+    /// Counts the matching lines and the individual matches in the content,
+    /// stopping after a maximum number of matching lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content to search within
+    /// * `limit` - Stop after this many matching lines, or `None` for no extra limit
+    ///
+    /// # Returns
+    ///
+    /// * `(usize, usize)` - The number of matching lines and the number of individual matches
+    pub fn count_matches_with_limit(&self, contents: &str, limit: Option<usize>) -> (usize, usize) {
+        // Proximity and multiline matches depend on the surrounding lines
+        if self.options.near.is_some() || self.multiline.is_some() {
+            return self
+                .search_with_limit(contents, limit)
                 .iter()
-                .filter(|&pattern| {
-                    if ignore_case {
-                        lines[line_number].to_lowercase().contains(pattern)
-                    } else {
-                        lines[line_number].contains(pattern)
+                .filter(|result| !result.is_context())
+                .fold((0, 0), |(lines, matches), result| {
+                    (lines + 1, matches + result.get_match_spans().len().max(1))
+                });
+        }
+
+        self.searched_parts(contents)
+            .flatten()
+            .filter(|part| self.is_selected(&part.text))
+            .take(self.max_count(limit).unwrap_or(usize::MAX))
+            .map(|part| self.line_matches(&part.text).1.len().max(1))
+            .fold((0, 0), |(lines, matches), count| {
+                (lines + 1, matches + count)
+            })
+    }
+
+    /// Checks whether the content contains at least one matching line.
+    ///
+    /// Stops scanning at the first matching line.
+    pub fn contains_match(&self, contents: &str) -> bool {
+        if self.max_count(None) == Some(0) {
+            return false;
+        }
+
+        if self.multiline.is_some() {
+            return !self.search_with_limit(contents, Some(1)).is_empty();
+        }
+
+        match self.options.near {
+            Some(distance) => {
+                let parts: Vec<Option<SearchedPart>> = self.searched_parts(contents).collect();
+                let texts: Vec<&str> = parts
+                    .iter()
+                    .map(|part| part.as_ref().map_or("", |part| &part.text))
+                    .collect();
+                !self.near_windows(&texts, distance).is_empty()
+            }
+            None => self
+                .searched_parts(contents)
+                .flatten()
+                .any(|part| self.is_selected(&part.text)),
+        }
+    }
+
+    /// Checks the file-level patterns against the whole content.
+    ///
+    /// A file is accepted if every `all_of` pattern occurs on some line, not
+    /// necessarily the same one, and no `none_of` pattern occurs on any line.
+    pub fn accepts_file(&self, contents: &str) -> bool {
+        let occurs = |matcher: &Matcher| self.records(contents).any(|line| matcher.is_match(line));
+        self.all_of.iter().all(occurs) && !self.none_of.iter().any(occurs)
+    }
+
+    /// Finds the windows of at most `distance` lines apart in which every
+    /// pattern occurs.
+    ///
+    /// For each line, the window is the shortest one ending there, so
+    /// overlapping windows are returned when patterns occur repeatedly. With
+    /// a boolean query, the windows are built from the terms outside of any
+    /// `NOT`, and a window is kept only if the whole query holds for the
+    /// terms occurring anywhere in it.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(usize, usize)>` - The first and last line number of each window, in order
+    fn near_windows(&self, lines: &[&str], distance: usize) -> Vec<(usize, usize)> {
+        let expression = self.matcher.expression.as_ref();
+        // The patterns that have to occur in every window
+        let required: Vec<bool> = expression.map_or_else(
+            || vec![true; self.matcher.patterns.len()],
+            |expression| expression.positive_terms.clone(),
+        );
+        let line_patterns: Vec<Vec<usize>> = lines
+            .iter()
+            .map(|line| {
+                let mut patterns: Vec<usize> = self
+                    .matcher
+                    .pattern_matches(line)
+                    .into_iter()
+                    .map(|(pattern_index, _)| pattern_index)
+                    .collect();
+                patterns.dedup();
+                patterns
+            })
+            .collect();
+
+        // The last line each pattern occurred on
+        let mut last_seen = vec![None; self.matcher.patterns.len()];
+        let mut windows = Vec::new();
+
+        for (line_number, patterns) in line_patterns.iter().enumerate() {
+            if !patterns
+                .iter()
+                .any(|&pattern_index| required[pattern_index])
+            {
+                continue;
+            }
+            for &pattern_index in patterns {
+                last_seen[pattern_index] = Some(line_number);
+            }
+
+            // The window starts at the required pattern seen longest ago
+            let first = last_seen
+                .iter()
+                .zip(&required)
+                .filter(|(_, required)| **required)
+                .try_fold(line_number, |first, (seen, _)| {
+                    seen.map(|seen| first.min(seen))
+                });
+            let Some(first) = first else {
+                continue;
+            };
+            if line_number - first > distance {
+                continue;
+            }
+
+            if let Some(expression) = expression {
+                let mut matched = vec![false; self.matcher.patterns.len()];
+                for &pattern_index in line_patterns[first..=line_number].iter().flatten() {
+                    matched[pattern_index] = true;
+                }
+                if !expression.expr.evaluate(&matched) {
+                    continue;
+                }
+            }
+            windows.push((first, line_number));
+        }
+
+        windows
+    }
+
+    /// Matches the multiline patterns against the whole content and splits
+    /// each match into the parts on the lines it covers.
+    ///
+    /// A match ending right after a line break doesn't cover the next line.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<MultilineMatches>` - The matches, or `None` when not in multiline mode
+    fn multiline_matches(
+        &self,
+        contents: &str,
+        lines: &[&str],
+        line_starts: &[usize],
+    ) -> Option<MultilineMatches> {
+        let regexes = self.multiline.as_ref()?;
+        let folded = FoldedText::new(contents, self.matcher.folding);
+
+        let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+        let mut matches = MultilineMatches {
+            ranges: Vec::new(),
+            lines: HashMap::new(),
+        };
+        if lines.is_empty() {
+            return Some(matches);
+        }
+
+        for (pattern_index, regex) in regexes.iter().enumerate() {
+            for m in regex.find_iter(folded.as_str()) {
+                let (start, end) = folded.original_span(m.start(), m.end());
+                // There is no line after a trailing line break
+                let terminator = if self.options.null_data { '\0' } else { '\n' };
+                if start == contents.len() && contents.ends_with(terminator) {
+                    continue;
+                }
+                let first = line_of(start).min(lines.len() - 1);
+                let last = if end > start { line_of(end - 1) } else { first };
+
+                for line_number in first..=last {
+                    let line_start = line_starts[line_number];
+                    let line_end = line_start + lines[line_number].len();
+                    let span = (
+                        start.clamp(line_start, line_end) - line_start,
+                        end.clamp(line_start, line_end) - line_start,
+                    );
+                    let line_matches = matches.lines.entry(line_number).or_default();
+                    // The line break at the end of a line has nothing to highlight
+                    if span.0 < span.1 || start == end {
+                        line_matches.push((pattern_index, span));
                     }
-                })
-                .cloned()
+                }
+                matches.ranges.push((first, last));
+            }
+        }
+
+        matches.ranges.sort();
+        Some(matches)
+    }
+
+    /// Returns the byte offset in the content where each line starts, for
+    /// the lines returned by [`Searcher::records`].
+    fn record_starts(&self, contents: &str) -> Vec<usize> {
+        if let Some(format) = self.options.table_format {
+            return table_rows(contents, format.delimiter())
+                .into_iter()
+                .map(|(start, _)| start)
                 .collect();
+        }
 
-            results.push(SearchResult::new(
-                line_number,
-                lines[line_number].to_string(),
-                matching_patterns,
-            ));
+        match &self.record_separator {
+            Some(separator) => separated_records(separator, contents)
+                .into_iter()
+                .map(|(start, _)| start)
+                .collect(),
+            None if self.options.null_data => line_starts(contents, '\0'),
+            None => line_starts(contents, '\n'),
         }
+    }
 
-        Ok(results)
+    /// Returns the part of each line that is matched, with its byte offset
+    /// in the line.
+    ///
+    /// That is the whole line, except in a table with a column to search,
+    /// where it's the text of that column in each row, unescaped if it's
+    /// quoted. The header row of a table is never matched, and neither are
+    /// rows without the column.
+    ///
+    /// # Returns
+    ///
+    /// * An iterator yielding, for each line, the part to match, or `None` if
+    ///   nothing on the line is matched
+    fn searched_parts<'a>(
+        &self,
+        contents: &'a str,
+    ) -> Box<dyn Iterator<Item = Option<SearchedPart<'a>>> + 'a> {
+        let mut lines = self.records(contents);
+        let Some(format) = self.options.table_format else {
+            return Box::new(lines.map(|line| Some(SearchedPart::whole(line))));
+        };
+        let Some(header) = lines.next() else {
+            return Box::new(iter::empty());
+        };
+
+        let delimiter = format.delimiter();
+        let column = match &self.options.table_column {
+            None => None,
+            Some(TableColumn::Index(index)) => Some(*index),
+            Some(TableColumn::Name(name)) => {
+                let header = header.strip_prefix('\u{feff}').unwrap_or(header);
+                let position = table_fields(header, delimiter)
+                    .into_iter()
+                    .position(|(start, end)| unquote_field(&header[start..end]).trim() == name);
+                match position {
+                    Some(position) => Some(position),
+                    // No row can match in a table without the column
+                    None => return Box::new(iter::once(None).chain(lines.map(|_| None))),
+                }
+            }
+        };
+
+        Box::new(iter::once(None).chain(lines.map(move |row| {
+            match column {
+                None => Some(SearchedPart::whole(row)),
+                Some(column) => table_fields(row, delimiter)
+                    .get(column)
+                    .map(|&span| SearchedPart::field(row, span)),
+            }
+        })))
+    }
+
+    /// Returns true if the line is selected, taking inverted matching into account.
+    fn is_selected(&self, line: &str) -> bool {
+        (self.matcher.is_match(line) && self.json_fields_match(line)) != self.options.invert
+    }
+
+    /// Returns true if the line holds JSON satisfying every field predicate.
+    ///
+    /// Without predicates every line passes, and with them lines that aren't
+    /// valid JSON never do.
+    fn json_fields_match(&self, line: &str) -> bool {
+        if self.json_fields.is_empty() {
+            return true;
+        }
+        serde_json::from_str::<serde_json::Value>(line).is_ok_and(|value| {
+            self.json_fields
+                .iter()
+                .all(|predicate| predicate.is_match(&value))
+        })
     }
+
+    /// Returns the matching patterns and match spans for a selected line.
+    ///
+    /// Lines selected by inverted matching have nothing to highlight.
+    fn line_matches(&self, line: &str) -> (Vec<usize>, Vec<(usize, usize)>) {
+        if self.options.invert {
+            (Vec::new(), Vec::new())
+        } else {
+            self.matcher.find_matches(line)
+        }
+    }
+
+    /// Combines the configured max count with an extra limit, the smaller one wins.
+    fn max_count(&self, limit: Option<usize>) -> Option<usize> {
+        match (self.options.max_count, limit) {
+            (Some(max_count), Some(limit)) => Some(max_count.min(limit)),
+            (max_count, limit) => max_count.or(limit),
+        }
+    }
+}
+
+/// Returns the byte offset in the content where each line starts.
+///
+/// Lines are counted like [`Searcher::records`] counts them, and each offset
+/// includes the full `\n`, `\r\n` or NUL terminators of the lines before it.
+///
+/// # Arguments
+///
+/// * `contents` - The text content to split
+/// * `terminator` - The character that ends each line
+fn line_starts(contents: &str, terminator: char) -> Vec<usize> {
+    contents
+        .split_inclusive(terminator)
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some(start)
+        })
+        .collect()
+}
+
+/// Splits content into the records between matches of a separator.
+///
+/// A line break at the very end of the content doesn't belong to the last
+/// record, and nothing after a final separator doesn't count as a record.
+/// Empty matches of the separator are ignored.
+///
+/// # Returns
+///
+/// * `Vec<(usize, usize)>` - The `(start, end)` byte offsets of every record
+fn separated_records(separator: &Regex, contents: &str) -> Vec<(usize, usize)> {
+    let mut records = Vec::new();
+    let mut start = 0;
+
+    for m in separator.find_iter(contents).filter(|m| !m.is_empty()) {
+        records.push((start, m.start()));
+        start = m.end();
+    }
+
+    if start < contents.len() {
+        let rest = &contents[start..];
+        let rest = rest
+            .strip_suffix('\n')
+            .map(|rest| rest.strip_suffix('\r').unwrap_or(rest))
+            .unwrap_or(rest);
+        records.push((start, start + rest.len()));
+    }
+
+    records
+}
+
+/// Finds the substring of the text with the smallest edit distance to the
+/// pattern.
+///
+/// This is the Levenshtein distance with the match free to start and end
+/// anywhere in the text (Sellers' algorithm), computed one text character at
+/// a time. Among equally close substrings, the one closest in length to the
+/// pattern wins, then the leftmost.
+///
+/// # Arguments
+///
+/// * `pattern` - The characters to look for
+/// * `text` - The text to search
+///
+/// # Returns
+///
+/// * `Option<(usize, (usize, usize))>` - The edit distance and the `(start, end)`
+///   byte offsets of the substring, or `None` if the text is empty
+fn closest_substring(pattern: &[char], text: &str) -> Option<(usize, (usize, usize))> {
+    let offsets: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_offset = |index: usize| offsets.get(index).map_or(text.len(), |&(offset, _)| offset);
+
+    // Distance and start character of the best alignment of each pattern
+    // prefix ending before the current text character
+    let mut previous: Vec<(usize, usize)> = (0..=pattern.len()).map(|i| (i, 0)).collect();
+    let mut current = previous.clone();
+    // Distance, length difference, start and end character of the best match
+    let mut best: Option<(usize, usize, usize, usize)> = None;
+
+    for (index, &(_, c)) in offsets.iter().enumerate() {
+        let end = index + 1;
+        // A match can start at any character
+        current[0] = (0, end);
+
+        for i in 1..=pattern.len() {
+            let substitute = (
+                previous[i - 1].0 + usize::from(pattern[i - 1] != c),
+                previous[i - 1].1,
+            );
+            let delete = (current[i - 1].0 + 1, current[i - 1].1);
+            let insert = (previous[i].0 + 1, previous[i].1);
+            current[i] = [delete, insert].into_iter().fold(substitute, |best, cell| {
+                if cell.0 < best.0 { cell } else { best }
+            });
+        }
+
+        let (distance, start) = current[pattern.len()];
+        let candidate = (distance, (end - start).abs_diff(pattern.len()), start, end);
+        if best.is_none_or(|best| (candidate.0, candidate.1) < (best.0, best.1)) {
+            best = Some(candidate);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    best.map(|(distance, _, start, end)| (distance, (byte_offset(start), byte_offset(end))))
+}
+
+/// Decides whether a pattern is interpreted as a regex.
+///
+/// In auto mode a pattern is a regex if it contains regex metacharacters.
+fn is_regex(pattern_mode: PatternMode, pattern: &str) -> bool {
+    match pattern_mode {
+        PatternMode::Literal => false,
+        PatternMode::Regex => true,
+        PatternMode::Auto => {
+            // Check if the pattern looks like a regex pattern
+            let regex_indicators = [
+                '*', '+', '?', '.', '\\', '[', ']', '(', ')', '{', '}', '^', '$',
+            ];
+            pattern.chars().any(|c| regex_indicators.contains(&c))
+        }
+    }
+}
+
+/// Decides whether a pattern is matched case-insensitively.
+///
+/// With smart case a pattern ignores case unless it contains an uppercase
+/// letter. In regex patterns, letters that are part of escape sequences
+/// (`\W`, `\p{Lu}`) or group names (`(?P<Name>...)`) don't count.
+///
+/// # Arguments
+///
+/// * `case_mode` - The requested case handling
+/// * `pattern` - The pattern to inspect
+/// * `is_regex` - Whether the pattern is a regular expression
+fn ignores_case(case_mode: CaseMode, pattern: &str, is_regex: bool) -> bool {
+    match case_mode {
+        CaseMode::Sensitive => false,
+        CaseMode::Insensitive => true,
+        CaseMode::Smart if !is_regex => !pattern.chars().any(char::is_uppercase),
+        CaseMode::Smart => {
+            let mut chars = pattern.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => match chars.next() {
+                        // Unicode classes like \p{Lu} or \PL
+                        Some('p' | 'P') => {
+                            if chars.next_if_eq(&'{').is_some() {
+                                chars.by_ref().find(|&c| c == '}');
+                            } else {
+                                chars.next();
+                            }
+                        }
+                        // Hex escapes like \x{2B}, \x2B, \u00C9 or \U0001F600
+                        Some(escape @ ('x' | 'u' | 'U')) => {
+                            if chars.next_if_eq(&'{').is_some() {
+                                chars.by_ref().find(|&c| c == '}');
+                            } else {
+                                let digits = match escape {
+                                    'x' => 2,
+                                    'u' => 4,
+                                    _ => 8,
+                                };
+                                for _ in 0..digits {
+                                    chars.next_if(char::is_ascii_hexdigit);
+                                }
+                            }
+                        }
+                        // Any other escaped character
+                        _ => {}
+                    },
+                    // Group names like (?P<Name>...) or (?<Name>...)
+                    '(' if chars.next_if_eq(&'?').is_some() => {
+                        chars.next_if_eq(&'P');
+                        if chars.next_if_eq(&'<').is_some() {
+                            chars.by_ref().find(|&c| c == '>');
+                        }
+                    }
+                    c if c.is_uppercase() => return false,
+                    _ => {}
+                }
+            }
+
+            true
+        }
+    }
+}
+
+/// Checks whether a match satisfies the required match boundary.
+///
+/// A word match must not be preceded or followed by a word character
+/// (alphanumeric or `_`), and a line match must cover the whole line.
+fn within_boundary(line: &str, start: usize, end: usize, boundary: MatchBoundary) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    match boundary {
+        MatchBoundary::None => true,
+        MatchBoundary::Word => {
+            !line[..start].chars().next_back().is_some_and(is_word_char)
+                && !line[end..].chars().next().is_some_and(is_word_char)
+        }
+        MatchBoundary::Line => start == 0 && end == line.len(),
+    }
+}
+
+/// Sorts spans by start position and merges the overlapping ones.
+///
+/// Adjacent spans are kept apart so that repeated matches are still counted
+/// individually.
+fn merge_spans(mut spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    spans.sort_by_key(|&(start, _)| start);
+
+    let mut merged_spans: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in spans {
+        match merged_spans.last_mut() {
+            // If this span overlaps with the previous one, merge them
+            Some((_, prev_end)) if start < *prev_end => *prev_end = end.max(*prev_end),
+            _ => merged_spans.push((start, end)),
+        }
+    }
+
+    merged_spans
 }
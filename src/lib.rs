@@ -19,8 +19,14 @@ pub mod core;
 pub mod models;
 mod utils;
 
-pub use models::{Config, SearchResult, SearchStats, ContextFlag};
+pub use core::Searcher;
+pub use models::{
+    CaseMode, Config, ContextSpec, LineEnding, LineEndingStyle, MatchBoundary, Normalization,
+    OutputMode, PatternMode, SearchOptions, SearchOutcome, SearchResult, SearchStats, TableColumn,
+    TableFormat,
+};
 use std::{
+    collections::BTreeMap,
     error, fs,
     io::{self, ErrorKind},
     path, time,
@@ -34,16 +40,17 @@ use std::{
 ///
 /// # Returns
 ///
-/// * `Result<(), Box<dyn error::Error>>` - Success or an error message
+/// * `Result<SearchOutcome, Box<dyn error::Error>>` - Whether anything matched, or an error message
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use minigrep::{config, Config};
-/// 
+///
 /// let args = std::env::args();
 /// let config = config::parse_args(args).unwrap();
-/// minigrep::run(config).unwrap();
+/// let outcome = minigrep::run(config).unwrap();
+/// std::process::exit(outcome.exit_code());
 /// ```
 ///
 /// # Errors
@@ -51,10 +58,19 @@ use std::{
 /// Returns an error if:
 /// - The specified file(s) can't be found or read
 /// - A directory is specified without the recursive flag
-/// - The search operation encounters an error
-pub fn run(config: Config) -> Result<(), Box<dyn error::Error>> {
+/// - The query contains an invalid regex pattern
+///
+/// Errors while reading an individual file during a recursive search are
+/// reported on stderr and the search continues; they are reflected as
+/// `SearchOutcome::Failed`.
+pub fn run(config: Config) -> Result<SearchOutcome, Box<dyn error::Error>> {
     let start_time = time::Instant::now();
-    let mut stats = SearchStats::init_stats(&config);
+    let searcher = config.search_options().build()?;
+    let mut stats = SearchStats::init_stats(&searcher);
+    stats.dry_run = config.dry_run;
+    let mut outcome = SearchOutcome::NoMatch;
+    // Matching lines still allowed across all files by --max-total
+    let mut remaining_total = config.max_total;
 
     // Check file existence upfront
     // If not a directory and file 1 doesn't exist
@@ -76,40 +92,43 @@ pub fn run(config: Config) -> Result<(), Box<dyn error::Error>> {
     // If recursive flag is set, get all files from directory
     if config.recursive {
         let files = utils::get_all_files_in_directory(&config.file_path_1)?;
-        stats.files_searched = files.len();
 
         for file_path in files {
-            if let Ok(contents) = fs::read_to_string(&file_path) {
-                stats.total_lines += contents.lines().count();
-
-                // Handle the Result returned by search
-                match core::search(
-                    &config.query,
-                    &contents,
-                    config.context_flag.as_str(),
-                    Some(config.context_count as usize),
-                    config.ignore_case,
-                ) {
-                    Ok(search_results) => {
-                        stats.update_match_count(&search_results, &config);
-                        if !search_results.is_empty() {
-                            if let Some(path_str) = file_path.to_str() {
-                                core::display_results(
-                                    path_str,
-                                    &search_results,
-                                    config.ignore_case,
-                                );
-                            }
-                        }
-                        // If results is empty, move on to the next file
-                    }
-                    Err(e) => {
-                        if let Some(path_str) = file_path.to_str() {
-                            eprintln!("Error searching file {}: {}", path_str, e);
+            // Stop the entire search once --max-total is reached
+            if remaining_total == Some(0) {
+                break;
+            }
+            stats.files_searched += 1;
+
+            match fs::read_to_string(&file_path) {
+                Ok(contents) => {
+                    stats.total_lines += searcher.records(&contents).count();
+
+                    if let Some(path_str) = file_path.to_str() {
+                        let file_outcome = search_file(
+                            path_str,
+                            &contents,
+                            &config,
+                            &searcher,
+                            &mut stats,
+                            &mut remaining_total,
+                            false,
+                        );
+
+                        // In quiet mode the first match decides the outcome
+                        if config.quiet && file_outcome == SearchOutcome::Matched {
+                            return Ok(file_outcome);
                         }
-                        // Continue with next file instead of stopping the entire search
+                        outcome = outcome.combine(file_outcome);
                     }
                 }
+                // Skip binary files that aren't valid UTF-8
+                Err(e) if e.kind() == ErrorKind::InvalidData => {}
+                // Report unreadable files and continue with the next one
+                Err(e) => {
+                    eprintln!("Error reading file {}: {}", file_path.display(), e);
+                    outcome = outcome.combine(SearchOutcome::Failed);
+                }
             }
         }
     } else {
@@ -128,73 +147,310 @@ pub fn run(config: Config) -> Result<(), Box<dyn error::Error>> {
 
         // File is not a directory, continue with search
         let file_1 = fs::read_to_string(&config.file_path_1)?;
-        stats.total_lines += file_1.lines().count();
+        stats.total_lines += searcher.records(&file_1).count();
         stats.files_searched += 1;
 
         let file_2 = if config.file_path_2.is_empty() {
             None
         } else {
             let content = fs::read_to_string(&config.file_path_2)?;
-            stats.total_lines += content.lines().count();
+            stats.total_lines += searcher.records(&content).count();
             stats.files_searched += 1;
             Some(content)
         };
 
         // Search and display file 1
-        match core::search(
-            &config.query,
+        // Don't return early on errors, continue with file 2 if it exists
+        outcome = search_file(
+            &config.file_path_1,
             &file_1,
-            config.context_flag.as_str(),
-            Some(config.context_count as usize),
-            config.ignore_case,
-        ) {
-            Ok(search_results_1) => {
-                stats.update_match_count(&search_results_1, &config);
-                core::display_results(&config.file_path_1, &search_results_1, config.ignore_case);
-            }
-            Err(e) => {
-                eprintln!("Error searching file {}: {}", config.file_path_1, e);
-                // Don't return early, continue with file 2 if it exists
-            }
+            &config,
+            &searcher,
+            &mut stats,
+            &mut remaining_total,
+            true,
+        );
+
+        // In quiet mode the first match decides the outcome
+        if config.quiet && outcome == SearchOutcome::Matched {
+            return Ok(outcome);
         }
 
-        // If file 2 exists, search and display it too
-        if let Some(file_2_contents) = file_2 {
-            match core::search(
-                &config.query,
+        // If file 2 exists, search and display it too unless --max-total is reached
+        if let Some(file_2_contents) = file_2
+            && remaining_total != Some(0)
+        {
+            let file_outcome = search_file(
+                &config.file_path_2,
                 &file_2_contents,
-                config.context_flag.as_str(),
-                Some(config.context_count as usize),
-                config.ignore_case,
+                &config,
+                &searcher,
+                &mut stats,
+                &mut remaining_total,
+                true,
+            );
+            outcome = outcome.combine(file_outcome);
+        }
+    }
+
+    // Print stats if requested
+    if config.show_stats && !config.quiet {
+        stats.duration = start_time.elapsed();
+        stats.display();
+    }
+
+    Ok(outcome)
+}
+
+/// Searches the contents of a single file and displays the outcome
+/// according to the configured output mode.
+///
+/// # Arguments
+///
+/// * `file_label` - Name or path of the file being searched
+/// * `contents` - The text content of the file
+/// * `config` - The search configuration
+/// * `searcher` - The compiled search to run on the contents
+/// * `stats` - Statistics to update with the matches found
+/// * `remaining_total` - Matching lines still allowed by `--max-total`, reduced
+///   by the matching lines found in this file
+/// * `report_empty` - Whether to report files without matches in line output
+///
+/// # Returns
+///
+/// * `SearchOutcome` - Whether the file matched
+fn search_file(
+    file_label: &str,
+    contents: &str,
+    config: &Config,
+    searcher: &Searcher,
+    stats: &mut SearchStats,
+    remaining_total: &mut Option<usize>,
+    report_empty: bool,
+) -> SearchOutcome {
+    // Detecting the line endings reads every byte, so it's only done for the stats
+    if config.show_stats {
+        stats.record_line_endings(file_label, contents);
+    }
+
+    // Files failing the file-level patterns have no matching lines, which
+    // the count and files-without-match modes still report
+    let accepted = searcher.accepts_file(contents);
+    if !accepted {
+        stats.files_filtered += 1;
+    }
+
+    // Quiet mode prints nothing and only needs to know whether anything matches
+    if config.quiet {
+        return if accepted && searcher.contains_match(contents) {
+            SearchOutcome::Matched
+        } else {
+            SearchOutcome::NoMatch
+        };
+    }
+
+    // Each mode yields the number of matching lines found in this file
+    let matching_lines = match config.output_mode {
+        // Nothing is shown or rewritten for a filtered out file
+        OutputMode::Lines | OutputMode::FilesWithMatches if !accepted => 0,
+        OutputMode::Lines if config.in_place => {
+            match rewrite_file(
+                file_label,
+                contents,
+                config,
+                searcher,
+                stats,
+                *remaining_total,
             ) {
-                Ok(search_results_2) => {
-                    stats.update_match_count(&search_results_2, &config);
-                    core::display_results(
-                        &config.file_path_2,
-                        &search_results_2,
-                        config.ignore_case,
-                    );
-                }
+                Ok(matching_lines) => matching_lines,
                 Err(e) => {
-                    eprintln!("Error searching file {}: {}", config.file_path_2, e);
+                    eprintln!("Error rewriting file {}: {}", file_label, e);
+                    return SearchOutcome::Failed;
                 }
             }
         }
+        OutputMode::Lines => {
+            let search_results = searcher.search_with_limit(contents, *remaining_total);
+            stats.update_match_count(&search_results);
+            if report_empty || !search_results.is_empty() {
+                core::display_results(file_label, &search_results, config);
+            }
+            search_results
+                .iter()
+                .filter(|result| !result.is_context())
+                .count()
+        }
+        OutputMode::Count | OutputMode::CountMatches => {
+            let (lines, matches) = if accepted {
+                searcher.count_matches_with_limit(contents, *remaining_total)
+            } else {
+                (0, 0)
+            };
+            // Counting doesn't attribute matches to patterns, so the lines are
+            // searched again for the per-pattern statistics
+            if config.show_stats && accepted {
+                stats.update_match_count(&searcher.search_with_limit(contents, *remaining_total));
+            } else {
+                stats.total_matches += matches;
+            }
+            if config.output_mode == OutputMode::Count {
+                core::display_count(file_label, lines, config);
+            } else {
+                core::display_count(file_label, matches, config);
+            }
+            lines
+        }
+        OutputMode::FilesWithMatches | OutputMode::FilesWithoutMatch => {
+            let matched =
+                accepted && *remaining_total != Some(0) && searcher.contains_match(contents);
+            if matched == (config.output_mode == OutputMode::FilesWithMatches) {
+                core::display_file_name(file_label, config);
+            }
+            // Only the first matching line is looked at
+            usize::from(matched)
+        }
+    };
+
+    if let Some(remaining) = remaining_total.as_mut() {
+        *remaining = remaining.saturating_sub(matching_lines);
     }
 
-    // Print stats if requested
-    if config.show_stats {
-        stats.duration = start_time.elapsed();
-        stats.display();
+    if matching_lines > 0 {
+        stats.files_matched += 1;
+        SearchOutcome::Matched
+    } else {
+        SearchOutcome::NoMatch
+    }
+}
+
+/// Replaces the matches in a file with the replacement template, or shows
+/// the changes as a unified diff on a dry run.
+///
+/// Only the matching lines that actually change are written, and every line
+/// keeps its original line terminator.
+///
+/// # Arguments
+///
+/// * `file_path` - Path of the file to rewrite
+/// * `contents` - The text content of the file
+/// * `config` - The search configuration
+/// * `searcher` - The compiled search, with the replacement template
+/// * `stats` - Statistics to update with the matches and rewrites
+/// * `limit` - Maximum number of matching lines to replace, or `None` for no limit
+///
+/// # Returns
+///
+/// * `io::Result<usize>` - The number of matching lines in the file
+///
+/// # Errors
+///
+/// Returns an error if the file can't be rewritten.
+fn rewrite_file(
+    file_path: &str,
+    contents: &str,
+    config: &Config,
+    searcher: &Searcher,
+    stats: &mut SearchStats,
+    limit: Option<usize>,
+) -> io::Result<usize> {
+    let search_results = searcher.search_with_limit(contents, limit);
+    stats.update_match_count(&search_results);
+
+    let lines: Vec<&str> = searcher.records(contents).collect();
+    let matching_lines: Vec<&SearchResult> = search_results
+        .iter()
+        .filter(|result| !result.is_context())
+        .collect();
+    let changed_lines: Vec<&SearchResult> = matching_lines
+        .iter()
+        .copied()
+        .filter(|result| result.get_line_content() != lines[result.get_line_number()])
+        .collect();
+
+    if changed_lines.is_empty() {
+        return Ok(matching_lines.len());
     }
 
-    Ok(())
+    if config.dry_run {
+        // The diff shows physical lines, so a record spanning several lines
+        // (a table row or paragraph) is replaced as a whole
+        let physical_lines: Vec<&str> = contents.lines().collect();
+        let mut changes = BTreeMap::new();
+        let mut line_number = 0;
+        let mut counted = 0;
+        for result in &changed_lines {
+            let start = result.get_byte_offset();
+            line_number += contents[counted..start].matches('\n').count();
+            counted = start;
+            let last = line_number + lines[result.get_line_number()].matches('\n').count();
+            changes.insert(line_number, (last, result.get_line_content().to_string()));
+        }
+        core::display_diff(&utils::unified_diff(file_path, &physical_lines, &changes));
+    } else {
+        // Only the content of the changed lines is replaced, so every line
+        // keeps the terminator it ended with
+        let mut rewritten = String::with_capacity(contents.len());
+        let mut copied = 0;
+        for result in &changed_lines {
+            let start = result.get_byte_offset();
+            rewritten.push_str(&contents[copied..start]);
+            rewritten.push_str(result.get_line_content());
+            copied = start + lines[result.get_line_number()].len();
+        }
+        rewritten.push_str(&contents[copied..]);
+        utils::write_atomically(path::Path::new(file_path), &rewritten, config.backup)?;
+    }
+
+    stats.files_rewritten += 1;
+    stats.lines_rewritten += changed_lines.len();
+    Ok(matching_lines.len())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{config::parse_args, core::search, models::ContextFlag};
+    use crate::{
+        config::parse_args,
+        models::{
+            CaseMode, ConfigError, ContextSpec, LineEnding, LineEndingStyle, MatchBoundary,
+            Normalization, PatternMode, SearchOptions, SearchResult, TableColumn, TableFormat,
+        },
+        utils::{FoldedText, Folding, table_fields, table_rows, unified_diff},
+    };
     use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use std::{collections::BTreeMap, error};
+
+    /// Builds search options for the query and runs them on the contents.
+    fn search(
+        query: &str,
+        contents: &str,
+        context: ContextSpec,
+        ignore_case: bool,
+    ) -> Result<Vec<SearchResult>, Box<dyn error::Error>> {
+        let case_mode = if ignore_case {
+            CaseMode::Insensitive
+        } else {
+            CaseMode::Sensitive
+        };
+        let searcher = SearchOptions::new(query)
+            .case_mode(case_mode)
+            .context(context)
+            .build()?;
+
+        Ok(searcher.search(contents))
+    }
+
+    /// Folding that only ignores case.
+    fn case_folding() -> Folding {
+        Folding {
+            ignore_case: true,
+            ..Folding::default()
+        }
+    }
+
+    fn fold_case(text: &str) -> String {
+        case_folding().apply(text)
+    }
 
     #[test]
     fn build_config() {
@@ -210,13 +466,31 @@ mod tests {
         assert_eq!(config.file_path_1, "file_path");
     }
 
+    #[test]
+    fn build_config_with_independent_context() {
+        let args = [
+            "minigrep",
+            "query",
+            "file_path",
+            "-C",
+            "1",
+            "--before",
+            "2",
+            "-A",
+            "300",
+        ];
+
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+
+        assert_eq!(config.context, ContextSpec::new(2, 300));
+    }
+
     #[test]
     fn test_search_no_matches() {
         let query = "nonexistent";
         let contents = "Line 1\nLine 2\nLine 3";
-        let context_flag = ContextFlag::After.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::new(0, 1), true).unwrap();
         assert_eq!(results.len(), 0);
     }
 
@@ -225,7 +499,7 @@ mod tests {
         let query = "Line 2";
         let contents = "Line 1\nLine 2\nLine 3";
 
-        let results = search(query, contents, "", None, true).unwrap();
+        let results = search(query, contents, ContextSpec::default(), true).unwrap();
 
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].get_line_number() + 1, 2);
@@ -237,7 +511,7 @@ mod tests {
         let query = "line 2";
         let contents = "Line 1\nline 2\nLine 3";
 
-        let results = search(query, contents, "", None, false).unwrap();
+        let results = search(query, contents, ContextSpec::default(), false).unwrap();
 
         // Print formatted results for inspection
         // println!("Results: {:#?}, length: {}", results, results.len());
@@ -251,9 +525,8 @@ mod tests {
     fn test_search_before_only() {
         let query = "Line 3";
         let contents = "Line 1\nLine 2\nLine 3\nLine 4";
-        let context_flag = ContextFlag::Before.as_str();
 
-        let results = search(query, contents, context_flag, Some(3), true).unwrap();
+        let results = search(query, contents, ContextSpec::new(3, 0), true).unwrap();
         assert_eq!(results.len(), 3);
 
         // Sort by line number to ensure consistent order
@@ -269,9 +542,8 @@ mod tests {
     fn test_search_after_only() {
         let query = "Line 2";
         let contents = "Line 1\nLine 2\nLine 3\nLine 4";
-        let context_flag = ContextFlag::After.as_str();
 
-        let results = search(query, contents, context_flag, Some(3), true).unwrap();
+        let results = search(query, contents, ContextSpec::new(0, 3), true).unwrap();
         assert_eq!(results.len(), 3);
 
         let mut results_sorted = results.clone();
@@ -286,9 +558,8 @@ mod tests {
     fn test_search_before_and_after() {
         let query = "Line 3";
         let contents = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
         assert_eq!(results.len(), 3);
 
         let mut results_sorted = results.clone();
@@ -303,9 +574,8 @@ mod tests {
     fn test_search_at_beginning() {
         let query = "Line 1";
         let contents = "Line 1\nLine 2\nLine 3";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
         assert_eq!(results.len(), 2);
 
         let mut results_sorted = results.clone();
@@ -319,9 +589,8 @@ mod tests {
     fn test_search_at_end() {
         let query = "Line 3";
         let contents = "Line 1\nLine 2\nLine 3";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
         assert_eq!(results.len(), 2);
 
         let mut results_sorted = results.clone();
@@ -335,9 +604,8 @@ mod tests {
     fn test_search_multiple_matches() {
         let query = "Line";
         let contents = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
-        let context_flag = ContextFlag::After.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::new(0, 1), true).unwrap();
 
         // All lines should be included due to overlapping contexts
         assert_eq!(results.len(), 5);
@@ -347,9 +615,8 @@ mod tests {
     fn test_search_overlapping() {
         let query = "match";
         let contents = "Line 1\nLine 2\nmatch 3\nLine 4\nmatch 5\nLine 6";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
 
         // Should include all lines from 1-6 due to overlapping contexts
         assert_eq!(results.len(), 5);
@@ -368,9 +635,8 @@ mod tests {
     fn test_search_non_overlapping() {
         let query = "match";
         let contents = "Line 1\nmatch 2\nLine 3\nLine 4\nmatch 5\nLine 6";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
 
         // Should have two separate groups: [0,1,2] and [3,4,5]
         assert_eq!(results.len(), 6);
@@ -390,9 +656,8 @@ mod tests {
     fn test_search_large_ranges() {
         let query = "unique";
         let contents = "Line 1\nLine 2\nLine 3\nLine 4\nunique line\nLine 6\nLine 7";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(10), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(10), true).unwrap();
 
         // Should include all lines despite requesting more context than exists
         assert_eq!(results.len(), 7);
@@ -402,9 +667,8 @@ mod tests {
     fn test_search_empty_file() {
         let query = "anything";
         let contents = "";
-        let context_flag = ContextFlag::After.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::new(0, 1), true).unwrap();
 
         // Should return an empty vector since there are no matches
         assert_eq!(results.len(), 0);
@@ -414,9 +678,8 @@ mod tests {
     fn test_search_multiple_matches_same_line() {
         let query = "match";
         let contents = "match in a match line\nother line";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
 
         // Should include lines 0 and 1 without duplicates
         assert_eq!(results.len(), 2);
@@ -433,7 +696,7 @@ mod tests {
         let query = "Line \\d"; // Regex pattern matching "Line" followed by a digit
         let contents = "Line 1\nLine 2\nLine 3\nNo match";
 
-        let results = search(query, contents, "", None, true).unwrap();
+        let results = search(query, contents, ContextSpec::default(), true).unwrap();
 
         assert_eq!(results.len(), 3);
 
@@ -450,11 +713,11 @@ mod tests {
         let contents = "Line 1\nline 2\nLINE 3";
 
         // Case-sensitive search should match only the first line
-        let results_sensitive = search(query, contents, "", None, false).unwrap();
+        let results_sensitive = search(query, contents, ContextSpec::default(), false).unwrap();
         assert_eq!(results_sensitive.len(), 2);
 
         // Case-insensitive search should match all three lines
-        let results_insensitive = search(query, contents, "", None, true).unwrap();
+        let results_insensitive = search(query, contents, ContextSpec::default(), true).unwrap();
         assert_eq!(results_insensitive.len(), 3);
     }
 
@@ -463,7 +726,7 @@ mod tests {
         let query = "Line ["; // Invalid regex pattern (unclosed character class)
         let contents = "Line 1\nLine 2";
 
-        let result = search(query, contents, "", None, true);
+        let result = search(query, contents, ContextSpec::default(), true);
         assert!(result.is_err());
     }
 
@@ -471,9 +734,8 @@ mod tests {
     fn test_regex_with_context() {
         let query = "Line \\d";
         let contents = "Header\nLine 1\nMiddle\nLine 3\nFooter";
-        let context_flag = ContextFlag::Context.as_str();
 
-        let results = search(query, contents, context_flag, Some(1), true).unwrap();
+        let results = search(query, contents, ContextSpec::symmetric(1), true).unwrap();
 
         // Should include: Header, Line 1, Middle, Line 3, Footer (all 5 lines)
         assert_eq!(results.len(), 5);
@@ -484,9 +746,972 @@ mod tests {
         let query = "Line|Header";
         let contents = "Header\nLine 1\nMiddle\nLine 3\nFooter";
 
-        let results = search(query, contents, "", None, true).unwrap();
+        let results = search(query, contents, ContextSpec::default(), true).unwrap();
 
         // Should match lines with "Line" or "Header"
         assert_eq!(results.len(), 3);
     }
+
+    #[test]
+    fn test_count_matching_lines_and_matches() {
+        let contents = "match in a match line\nother line\nmatch";
+
+        let searcher = SearchOptions::new("match").build().unwrap();
+        assert_eq!(searcher.count_matches(contents), (2, 3));
+
+        let searcher = SearchOptions::new("match")
+            .max_count(Some(1))
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (1, 2));
+    }
+
+    #[test]
+    fn test_contains_match() {
+        let contents = "Line 1\nLine 2\nLine 3";
+
+        let insensitive = SearchOptions::new("line 2").case_mode(CaseMode::Insensitive);
+        assert!(insensitive.build().unwrap().contains_match(contents));
+
+        let sensitive = SearchOptions::new("line 2").case_mode(CaseMode::Sensitive);
+        assert!(!sensitive.build().unwrap().contains_match(contents));
+
+        let regex = SearchOptions::new("Line \\d");
+        assert!(regex.build().unwrap().contains_match(contents));
+    }
+
+    #[test]
+    fn test_search_with_limit_keeps_trailing_context() {
+        let contents = "match 1\nLine 2\nmatch 3\nLine 4\nmatch 5";
+        let searcher = SearchOptions::new("match")
+            .context(ContextSpec::new(0, 1))
+            .max_count(Some(2))
+            .build()
+            .unwrap();
+
+        let results = searcher.search(contents);
+
+        // Two matches plus the context line after each
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[3].get_line_number() + 1, 4); // Line 4 (context)
+        assert!(results[3].is_context());
+    }
+
+    #[test]
+    fn test_search_fixed_strings() {
+        let contents = "a.b\naxb";
+
+        // Auto mode treats the dot as a regex wildcard
+        let searcher = SearchOptions::new("a.b").build().unwrap();
+        assert_eq!(searcher.search(contents).len(), 2);
+
+        let searcher = SearchOptions::new("a.b")
+            .pattern_mode(PatternMode::Literal)
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].get_match_spans(), &[(0, 3)]);
+    }
+
+    #[test]
+    fn test_search_invert() {
+        let contents = "Line 1\nother\nLine 3";
+        let searcher = SearchOptions::new("Line").invert(true).build().unwrap();
+
+        let results = searcher.search(contents);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].get_line_content(), "other");
+        assert!(results[0].get_match_spans().is_empty());
+    }
+
+    #[test]
+    fn test_search_word_and_line_boundaries() {
+        let contents = "foo\nfoobar\nsay foo here";
+
+        let word = SearchOptions::new("foo").boundary(MatchBoundary::Word);
+        let results = word.build().unwrap().search(contents);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].get_match_spans(), &[(4, 7)]);
+
+        let line = SearchOptions::new("fo+").boundary(MatchBoundary::Line);
+        let results = line.build().unwrap().search(contents);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].get_line_content(), "foo");
+    }
+
+    #[test]
+    fn test_smart_case_per_literal_pattern() {
+        let contents = "Frog\nfrog\nBOG\nbog";
+        let searcher = SearchOptions::new("Frog|bog")
+            .case_mode(CaseMode::Smart)
+            .build()
+            .unwrap();
+
+        let results = searcher.search(contents);
+        let lines: Vec<&str> = results.iter().map(|r| r.get_line_content()).collect();
+
+        // "Frog" is case-sensitive, "bog" is not
+        assert_eq!(lines, vec!["Frog", "BOG", "bog"]);
+    }
+
+    #[test]
+    fn test_smart_case_ignores_regex_escapes() {
+        let contents = "a-frog\nA-FROG";
+
+        // \W is an escape sequence, not an uppercase letter
+        let searcher = SearchOptions::new("a\\Wfrog")
+            .case_mode(CaseMode::Smart)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.search(contents).len(), 2);
+
+        let searcher = SearchOptions::new("A\\WFROG")
+            .case_mode(CaseMode::Smart)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.search(contents).len(), 1);
+    }
+
+    #[test]
+    fn build_config_case_flag_precedence() {
+        let args = ["minigrep", "query", "file_path", "-i", "--smart-case"];
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+        assert_eq!(config.case_mode, CaseMode::Smart);
+
+        let args = ["minigrep", "query", "file_path", "--smart-case", "-S"];
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+        assert_eq!(config.case_mode, CaseMode::Sensitive);
+    }
+
+    #[test]
+    fn test_search_unicode_case_folding() {
+        let contents = "Die STRASSE ist lang\nΣΊΣΥΦΟΣ und straße";
+
+        let results = search("straße", contents, ContextSpec::default(), true).unwrap();
+        assert_eq!(results.len(), 2);
+
+        // Spans point into the original lines even when folding changes lengths
+        let line = results[0].get_line_content();
+        let (start, end) = results[0].get_match_spans()[0];
+        assert_eq!(&line[start..end], "STRASSE");
+
+        // Final sigma folds to the same letter as capital sigma
+        let results = search("σίσυφος", contents, ContextSpec::default(), true).unwrap();
+        let line = results[0].get_line_content();
+        let (start, end) = results[0].get_match_spans()[0];
+        assert_eq!(&line[start..end], "ΣΊΣΥΦΟΣ");
+    }
+
+    #[test]
+    fn test_folded_text_original_span() {
+        let folded = FoldedText::new("Maß und ẞ", case_folding());
+        assert_eq!(folded.as_str(), "mass und ss");
+
+        // "ss" of "Maß" maps back to the whole "ß"
+        assert_eq!(folded.original_span(2, 4), (2, 4));
+        // A range inside a single folded character is widened to that character
+        assert_eq!(folded.original_span(10, 11), (9, 12));
+    }
+
+    #[test]
+    fn build_config_with_pattern_flags() {
+        let args = ["minigrep", "-e", "a|b", "file_path", "--regexp", "c", "-i"];
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+        assert_eq!(config.query, "");
+        assert_eq!(config.patterns, vec!["a|b", "c"]);
+        assert_eq!(config.file_path_1, "file_path");
+
+        // Patterns can be added to a positional query too
+        let args = ["minigrep", "query", "file_path", "-e", "other"];
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+        assert_eq!(config.query, "query");
+        assert_eq!(config.patterns, vec!["other"]);
+
+        let args = ["minigrep", "-e", "query"];
+        assert!(parse_args(args.into_iter().map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_search_extra_patterns() {
+        let contents = "a | b\nab\nvalue: 42";
+
+        // An extra pattern keeps its literal pipe, and is checked for regex
+        // characters on its own
+        let searcher = SearchOptions::new("")
+            .patterns(vec!["a | b".to_string(), r"\d+".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(
+            searcher.patterns().collect::<Vec<_>>(),
+            vec!["a | b", r"\d+"]
+        );
+
+        let results = searcher.search(contents);
+        let lines: Vec<&str> = results.iter().map(|r| r.get_line_content()).collect();
+        assert_eq!(lines, vec!["a | b", "value: 42"]);
+        assert_eq!(results[0].get_matching_patterns(), [0]);
+        assert_eq!(results[1].get_matching_patterns(), [1]);
+
+        // Every pattern that hits a line is recorded, in the order given
+        let searcher = SearchOptions::new("value")
+            .patterns(vec!["42".to_string()])
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(results[0].get_matching_patterns(), [0, 1]);
+        assert_eq!(results[0].get_match_spans(), [(0, 5), (7, 9)]);
+    }
+
+    #[test]
+    fn test_search_overlapping_literal_patterns() {
+        let searcher = SearchOptions::new("abc|bcd|b|zzz").build().unwrap();
+
+        // Patterns overlapping each other are all recorded and their spans merged
+        let results = searcher.search("abcd");
+        assert_eq!(results[0].get_matching_patterns(), [0, 1, 2]);
+        assert_eq!(results[0].get_match_spans(), [(0, 4)]);
+
+        // Occurrences of the same pattern don't overlap, like `str::matches`
+        let searcher = SearchOptions::new("aa").build().unwrap();
+        let results = searcher.search("aaaaa");
+        assert_eq!(results[0].get_match_spans(), [(0, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn test_search_boolean_query() {
+        let contents =
+            "ERROR payment failed\nERROR payment failed, retry\nINFO payment ok\nERROR disk full";
+        let search = |query: &str| {
+            SearchOptions::new("")
+                .boolean_query(Some(query.to_string()))
+                .build()
+                .unwrap()
+                .search(contents)
+        };
+
+        let results = search("ERROR AND payment AND NOT retry");
+        assert_eq!(results.len(), 1);
+        // The terms are the patterns, so `ERROR` and `payment` are 0 and 1
+        assert_eq!(results[0].get_matching_patterns(), [0, 1]);
+        assert_eq!(results[0].get_match_spans(), [(0, 5), (6, 13)]);
+
+        // AND binds tighter than OR, and adjacent terms are joined with AND
+        assert_eq!(search("INFO OR ERROR disk").len(), 2);
+        assert_eq!(search("(INFO OR ERROR) disk").len(), 1);
+
+        // Quoted phrases keep their spaces, and negated terms aren't highlighted
+        let results = search(r#""payment ok" OR NOT "ERROR payment""#);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].get_matching_patterns(), [0]);
+        assert!(results[1].get_match_spans().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_boolean_query() {
+        for query in ["", "a AND", "(a OR b", "a )", "NOT", "\"unterminated"] {
+            let result = SearchOptions::new("")
+                .boolean_query(Some(query.to_string()))
+                .build();
+            assert!(result.is_err(), "{query:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_accepts_file() {
+        let searcher = SearchOptions::new("ERROR")
+            .all_of(vec!["ERROR".to_string(), "payment".to_string()])
+            .none_of(vec!["retry".to_string()])
+            .build()
+            .unwrap();
+
+        // The required patterns may occur on different lines
+        assert!(searcher.accepts_file("ERROR disk full\npayment done"));
+        assert!(!searcher.accepts_file("ERROR disk full"));
+        assert!(!searcher.accepts_file("ERROR payment\nretry"));
+
+        // Without file-level patterns every file is accepted
+        let searcher = SearchOptions::new("ERROR").build().unwrap();
+        assert!(searcher.accepts_file(""));
+
+        // File-level patterns are matched exactly, whatever the query does
+        let searcher = SearchOptions::new("ERROR|WARN")
+            .fuzzy(Some(1))
+            .all_of(vec!["disk|full".to_string()])
+            .build()
+            .unwrap();
+        assert!(searcher.accepts_file("ERROR disk|full"));
+        assert!(!searcher.accepts_file("ERROR disk full"));
+        assert!(!searcher.accepts_file("ERROR disk|fall"));
+    }
+
+    #[test]
+    fn build_config_file_patterns_without_query() {
+        let args = [
+            "minigrep",
+            "--all-of",
+            "a",
+            "file_path",
+            "--none-of",
+            "b",
+            "-e",
+            "c",
+        ];
+        let config = parse_args(args.into_iter().map(String::from)).unwrap();
+        assert_eq!(config.all_of, vec!["a"]);
+        assert_eq!(config.none_of, vec!["b"]);
+        assert_eq!(config.patterns, vec!["c"]);
+
+        // File-level patterns don't say which lines to show
+        let args = ["minigrep", "--all-of", "a", "file_path", "--none-of", "b"];
+        assert!(matches!(
+            parse_args(args.into_iter().map(String::from)),
+            Err(ConfigError::RequiresPattern(flag)) if flag == "--all-of"
+        ));
+        let args = ["minigrep", "--none-of", "b", "file_path"];
+        assert!(matches!(
+            parse_args(args.into_iter().map(String::from)),
+            Err(ConfigError::RequiresPattern(flag)) if flag == "--none-of"
+        ));
+    }
+
+    #[test]
+    fn test_search_near() {
+        let contents =
+            "timeout\nok\nok\ndb-primary\nok\nok\nok\nok\ntimeout\nok\nok\nok\nok\ndb-primary";
+        let searcher = SearchOptions::new("timeout|db-primary")
+            .near(Some(3))
+            .build()
+            .unwrap();
+
+        // Only the first pair is close enough, the lines between are context
+        let results = searcher.search(contents);
+        let lines: Vec<(usize, bool)> = results
+            .iter()
+            .map(|r| (r.get_line_number(), r.is_context()))
+            .collect();
+        assert_eq!(lines, vec![(0, false), (1, true), (2, true), (3, false)]);
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+        assert!(searcher.contains_match(contents));
+
+        let searcher = SearchOptions::new("timeout|db-primary")
+            .near(Some(2))
+            .build()
+            .unwrap();
+        assert!(searcher.search(contents).is_empty());
+        assert!(!searcher.contains_match(contents));
+
+        // A single pattern has nothing to be near
+        assert!(SearchOptions::new("timeout").near(Some(2)).build().is_err());
+
+        // Negated query terms must be absent from the window, not present
+        let searcher = SearchOptions::new("")
+            .boolean_query(Some("timeout AND db-primary AND NOT retry".to_string()))
+            .near(Some(3))
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+        assert!(!searcher.contains_match(
+            "timeout
+retry
+db-primary"
+        ));
+
+        let args = [
+            "minigrep",
+            "-e",
+            "a",
+            "-e",
+            "b",
+            "file_path",
+            "--near",
+            "1",
+            "--invert-match",
+        ];
+        assert!(matches!(
+            parse_args(args.into_iter().map(String::from)),
+            Err(ConfigError::ConflictingFlags(flag, other))
+                if flag == "--invert-match" && other == "--near"
+        ));
+    }
+
+    #[test]
+    fn test_search_multiline() {
+        let contents = "fn main() {\n    let x = 1;\n}\nfn other() {\n}";
+        let searcher = SearchOptions::new(r"\{\n\s+let")
+            .multiline(true)
+            .build()
+            .unwrap();
+
+        // Each line the match covers is a match, highlighted where it's covered
+        let results = searcher.search(contents);
+        let lines: Vec<(usize, &[(usize, usize)])> = results
+            .iter()
+            .map(|r| (r.get_line_number(), r.get_match_spans()))
+            .collect();
+        assert_eq!(lines, vec![(0, &[(10, 11)][..]), (1, &[(0, 7)][..])]);
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+
+        // Without dotall, `.` stops at line breaks
+        let searcher = SearchOptions::new("main.*x")
+            .multiline(true)
+            .build()
+            .unwrap();
+        assert!(!searcher.contains_match(contents));
+        let searcher = SearchOptions::new("main.*x")
+            .multiline(true)
+            .multiline_dotall(true)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+
+        // `^` and `$` match at every line, and inverting selects uncovered lines
+        let searcher = SearchOptions::new("^}$")
+            .multiline(true)
+            .invert(true)
+            .build()
+            .unwrap();
+        let lines: Vec<usize> = searcher
+            .search(contents)
+            .iter()
+            .map(|r| r.get_line_number())
+            .collect();
+        assert_eq!(lines, vec![0, 1, 3]);
+
+        // With null data, `^` and `$` match at NUL instead of line breaks
+        let contents = "one\nfoo\0two foo\0";
+        let search = |pattern: &str| {
+            SearchOptions::new(pattern)
+                .multiline(true)
+                .null_data(true)
+                .build()
+                .unwrap()
+                .count_matches(contents)
+        };
+        assert_eq!(search("foo$"), (2, 2));
+        assert_eq!(search("^two"), (1, 1));
+        assert_eq!(search("one$"), (0, 0));
+
+        assert!(
+            SearchOptions::new("a|b")
+                .multiline(true)
+                .near(Some(1))
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_fuzzy() {
+        let contents = "failed to receive packet\nfailed to recieve packet\nrecv done";
+        let searcher = SearchOptions::new("recieve|packte")
+            .fuzzy(Some(2))
+            .build()
+            .unwrap();
+
+        // The closest substring of each pattern is highlighted and its
+        // distance recorded
+        let results = searcher.search(contents);
+        let matches: Vec<_> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.get_line_number(),
+                    r.get_match_spans(),
+                    r.get_edit_distances(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            matches,
+            vec![
+                (0, &[(10, 17), (18, 23)][..], &[2, 1][..]),
+                (1, &[(10, 17), (18, 23)][..], &[0, 1][..]),
+            ]
+        );
+
+        // Distances stay with their matches when these are replaced
+        let searcher = SearchOptions::new("recieve|packte")
+            .fuzzy(Some(2))
+            .replace(Some("X".to_string()))
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(results[1].get_line_content(), "failed to X Xt");
+        assert_eq!(results[1].get_edit_distances(), &[0, 1]);
+
+        let searcher = SearchOptions::new("recieve")
+            .fuzzy(Some(1))
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (1, 1));
+
+        // Regex characters are taken literally, and case folding applies
+        let searcher = SearchOptions::new("PACKET.")
+            .fuzzy(Some(1))
+            .case_mode(CaseMode::Insensitive)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+
+        // As many edits as a pattern has characters would match any line
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(parse(&["minigrep", "recv", "file_path", "--fuzzy", "3"]).is_ok());
+        assert!(matches!(
+            parse(&["minigrep", "recv", "file_path", "--fuzzy", "4"]),
+            Err(ConfigError::FuzzyDistanceTooLarge(4, pattern)) if pattern == "recv"
+        ));
+        assert!(matches!(
+            parse(&["minigrep", "-e", "receive", "-e", "ok", "file_path", "--fuzzy", "2"]),
+            Err(ConfigError::FuzzyDistanceTooLarge(2, pattern)) if pattern == "ok"
+        ));
+        assert!(matches!(
+            parse(&["minigrep", "--query", "receive AND NOT ok", "file_path", "--fuzzy", "2"]),
+            Err(ConfigError::FuzzyDistanceTooLarge(2, pattern)) if pattern == "ok"
+        ));
+    }
+
+    #[test]
+    fn test_search_replace() {
+        let contents = "mail bob@example.com and al@x.com\nnothing here";
+        let searcher = SearchOptions::new(r"(\w+)@(?P<host>\w+)\.com")
+            .replace(Some("${host}:$1".to_string()))
+            .build()
+            .unwrap();
+
+        let results = searcher.search(contents);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].get_line_content(), "mail example:bob and x:al");
+        assert_eq!(results[0].get_match_spans(), &[(5, 16), (21, 25)]);
+        // Columns and offsets are those of the matches before replacement
+        assert_eq!(results[0].get_match_positions(), [(6, 5), (26, 25)]);
+
+        // Literal patterns split on `|` only know the whole match
+        let searcher = SearchOptions::new("bob|al")
+            .replace(Some("<$0$1>".to_string()))
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(
+            results[0].get_line_content(),
+            "mail <bob>@example.com and <al>@x.com"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let lines: Vec<String> = (1..=12).map(|n| format!("line {n}")).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let changes = BTreeMap::from([
+            (1, (1, "LINE 2".to_string())),
+            (2, (2, "LINE 3".to_string())),
+            (10, (10, "LINE 11\nnew line".to_string())),
+        ]);
+
+        // Changes far apart get their own hunks, and the second hunk starts
+        // one line later in the new file only after the first one's changes
+        assert_eq!(
+            unified_diff("file.txt", &lines, &changes),
+            "--- a/file.txt\n+++ b/file.txt\n\
+             @@ -1,6 +1,6 @@\n line 1\n-line 2\n-line 3\n+LINE 2\n+LINE 3\n line 4\n line 5\n line 6\n\
+             @@ -8,5 +8,6 @@\n line 8\n line 9\n line 10\n-line 11\n+LINE 11\n+new line\n line 12\n"
+        );
+        assert_eq!(unified_diff("file.txt", &lines, &BTreeMap::new()), "");
+
+        // A multi-line record is removed and added line by line
+        let changes = BTreeMap::from([(4, (5, "LINE 5\r\nLINE 6".to_string()))]);
+        assert_eq!(
+            unified_diff("file.txt", &lines, &changes),
+            "--- a/file.txt\n+++ b/file.txt\n\
+             @@ -2,8 +2,8 @@\n line 2\n line 3\n line 4\n-line 5\n-line 6\n+LINE 5\n+LINE 6\n\
+             \x20line 7\n line 8\n line 9\n"
+        );
+    }
+
+    #[test]
+    fn test_search_byte_offsets_and_columns() {
+        // "é" and "ö" take two bytes, and the first line ends with \r\n
+        let contents = "héllo wörld\r\nfoo\n\nwörld";
+        let searcher = SearchOptions::new("wörld")
+            .context(ContextSpec::new(0, 1))
+            .build()
+            .unwrap();
+
+        let results = searcher.search(contents);
+        let positions: Vec<(usize, usize, Option<usize>)> = results
+            .iter()
+            .map(|r| (r.get_line_number(), r.get_byte_offset(), r.get_column()))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(0, 0, Some(7)), (1, 15, None), (3, 20, Some(1))]
+        );
+        assert_eq!(&contents[15..18], "foo");
+    }
+
+    #[test]
+    fn test_line_endings() {
+        assert_eq!(LineEndingStyle::detect("a\nb\n"), LineEndingStyle::Lf);
+        assert_eq!(LineEndingStyle::detect("a\r\nb\r\n"), LineEndingStyle::CrLf);
+        assert_eq!(LineEndingStyle::detect("a\r\nb\n"), LineEndingStyle::Mixed);
+        assert_eq!(LineEndingStyle::detect("a"), LineEndingStyle::None);
+
+        let contents = "end;\r\nfoo end;\nend;";
+        let searcher = SearchOptions::new("end").build().unwrap();
+        let endings: Vec<LineEnding> = searcher
+            .search(contents)
+            .iter()
+            .map(SearchResult::get_line_ending)
+            .collect();
+        assert_eq!(
+            endings,
+            vec![LineEnding::CrLf, LineEnding::Lf, LineEnding::None]
+        );
+
+        // In multiline mode `$` only matches before `\r\n` with --crlf
+        let searcher = SearchOptions::new("end;$").multiline(true).build().unwrap();
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+        let searcher = SearchOptions::new("end;$")
+            .multiline(true)
+            .crlf(true)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.count_matches(contents), (3, 3));
+    }
+
+    #[test]
+    fn test_search_null_data() {
+        let contents = "first\nrecord foo\0second\0foo\0";
+        let searcher = SearchOptions::new("foo").null_data(true).build().unwrap();
+
+        let results = searcher.search(contents);
+        let records: Vec<(usize, &str, usize, LineEnding)> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.get_line_number(),
+                    r.get_line_content(),
+                    r.get_byte_offset(),
+                    r.get_line_ending(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (0, "first\nrecord foo", 0, LineEnding::Nul),
+                (2, "foo", 24, LineEnding::Nul),
+            ]
+        );
+        assert_eq!(searcher.records(contents).count(), 3);
+    }
+
+    #[test]
+    fn test_search_record_separator() {
+        let contents = "id=1\nstatus=ok\n---\nid=2\nstatus=timeout\n---\nid=3\nstatus=ok\n";
+        let searcher = SearchOptions::new("timeout")
+            .record_separator(Some("\n---\n".to_string()))
+            .context(ContextSpec::new(1, 0))
+            .build()
+            .unwrap();
+
+        // Whole records are matched and returned, numbered as records
+        let results = searcher.search(contents);
+        let records: Vec<(usize, &str, bool, usize)> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.get_line_number(),
+                    r.get_line_content(),
+                    r.is_context(),
+                    r.get_byte_offset(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (0, "id=1\nstatus=ok", true, 0),
+                (1, "id=2\nstatus=timeout", false, 19),
+            ]
+        );
+        // The final line break doesn't belong to the last record
+        assert_eq!(searcher.records(contents).last(), Some("id=3\nstatus=ok"));
+
+        assert!(
+            SearchOptions::new("timeout")
+                .record_separator(Some("(".to_string()))
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_json_fields() {
+        let contents = "{\"level\":\"error\",\"msg\":\"db timeout\",\"ms\":5000}\n\
+                        {\"level\":\"error\",\"msg\":\"disk full\",\"ms\":3}\n\
+                        level=error msg=timeout\n\
+                        {\"level\":\"info\",\"msg\":\"timeout retried\",\"ms\":\"800\"}\n";
+        let matching_lines = |predicates: &[&str], query: &str| -> Vec<usize> {
+            SearchOptions::new(query)
+                .json_fields(predicates.iter().map(|p| p.to_string()).collect())
+                .build()
+                .unwrap()
+                .search(contents)
+                .iter()
+                .map(|r| r.get_line_number())
+                .collect()
+        };
+
+        // Lines that aren't JSON never match a predicate
+        assert_eq!(matching_lines(&["level=error"], ""), vec![0, 1]);
+        assert_eq!(matching_lines(&["level=error", "msg~time"], ""), vec![0]);
+        assert_eq!(matching_lines(&["level!=error"], ""), vec![3]);
+        // Numeric comparisons also read numbers held in strings
+        assert_eq!(matching_lines(&["ms>=800"], ""), vec![0, 3]);
+        assert_eq!(matching_lines(&["ms=5000.0"], ""), vec![0]);
+        // The query must match as well
+        assert_eq!(matching_lines(&["level=error"], "disk"), vec![1]);
+
+        for invalid in ["level", "=error", "ms>fast", "msg~("] {
+            assert!(
+                SearchOptions::new("")
+                    .json_fields(vec![invalid.to_string()])
+                    .build()
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_table_rows_and_fields() {
+        let contents = "id,note\r\n1,\"a, b\"\n2,\"two\nlines\"\n3,5\" screen\n";
+        let rows: Vec<&str> = table_rows(contents, b',')
+            .into_iter()
+            .map(|(start, end)| &contents[start..end])
+            .collect();
+        assert_eq!(
+            rows,
+            vec!["id,note", "1,\"a, b\"", "2,\"two\nlines\"", "3,5\" screen"]
+        );
+
+        // Quoted fields are returned without their quotes
+        let field_texts = |row: &str| -> Vec<String> {
+            table_fields(row, b',')
+                .into_iter()
+                .map(|(start, end)| row[start..end].to_string())
+                .collect()
+        };
+        assert_eq!(field_texts("1,\"a, b\""), vec!["1", "a, b"]);
+        assert_eq!(
+            field_texts("\"say \"\"hi\"\"\",,x"),
+            vec!["say \"\"hi\"\"", "", "x"]
+        );
+        assert_eq!(field_texts("3,5\" screen"), vec!["3", "5\" screen"]);
+        assert_eq!(field_texts("a\tb"), vec!["a\tb"]);
+        assert_eq!(table_fields("a\tb", b'\t'), vec![(0, 1), (2, 3)]);
+    }
+
+    #[test]
+    fn test_search_table_column() {
+        let contents =
+            "name,city\nParis Hilton,London\nAnn,Paris\n\"Bob, Jr\",\"Paris,\nTX\"\nShort\n";
+        let searcher = SearchOptions::new("Paris")
+            .table_format(Some(TableFormat::Csv))
+            .table_column(Some(TableColumn::Name("city".to_string())))
+            .build()
+            .unwrap();
+
+        // The header comes first, and spans point into the whole row
+        let results = searcher.search(contents);
+        let rows: Vec<_> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.get_line_number(),
+                    r.get_line_content(),
+                    r.is_context(),
+                    r.get_match_spans(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "name,city", true, &[][..]),
+                (2, "Ann,Paris", false, &[(4, 9)][..]),
+                (3, "\"Bob, Jr\",\"Paris,\nTX\"", false, &[(11, 16)][..]),
+            ]
+        );
+        assert_eq!(searcher.count_matches(contents), (2, 2));
+
+        // Rows without the column never match, even inverted
+        let inverted = SearchOptions::new("Paris")
+            .table_format(Some(TableFormat::Csv))
+            .table_column(Some(TableColumn::Index(1)))
+            .invert(true)
+            .build()
+            .unwrap();
+        assert_eq!(inverted.count_matches(contents), (1, 1));
+
+        // Without the column in the header nothing matches
+        let missing = SearchOptions::new("Paris")
+            .table_format(Some(TableFormat::Csv))
+            .table_column(Some(TableColumn::Name("country".to_string())))
+            .build()
+            .unwrap();
+        assert!(!missing.contains_match(contents));
+
+        // Quoted fields are matched with `""` unescaped, but rows keep them
+        let contents = "id,note\n1,\"say \"\"hi\"\" now\"\n2,say \"\"hi\"\"\n";
+        let search = |replace: Option<&str>| {
+            SearchOptions::new("say \"hi\"")
+                .table_format(Some(TableFormat::Csv))
+                .table_column(Some(TableColumn::Name("note".to_string())))
+                .replace(replace.map(str::to_string))
+                .build()
+                .unwrap()
+                .search(contents)
+        };
+        let results = search(None);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].get_line_content(), "1,\"say \"\"hi\"\" now\"");
+        assert_eq!(results[1].get_match_spans(), [(3, 13)]);
+
+        // Replacements are escaped again so the row stays valid
+        let results = search(Some("<$0>"));
+        assert_eq!(results[1].get_line_content(), "1,\"<say \"\"hi\"\"> now\"");
+        assert_eq!(results[1].get_match_spans(), [(3, 15)]);
+        assert_eq!(results[1].get_match_positions(), [(4, 3)]);
+
+        assert!(
+            SearchOptions::new("Paris")
+                .table_column(Some(TableColumn::Index(1)))
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_normalization() {
+        // Precomposed "é" in the pattern, decomposed "e" + U+0301 in the line
+        let contents = "un cafe\u{301} noir\nun cafe noir";
+
+        let searcher = SearchOptions::new("café").build().unwrap();
+        assert!(searcher.search(contents).is_empty());
+
+        let searcher = SearchOptions::new("café")
+            .normalization(Normalization::Nfc)
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(results.len(), 1);
+
+        // The span covers the decomposed original text, accent included
+        let line = results[0].get_line_content();
+        let (start, end) = results[0].get_match_spans()[0];
+        assert_eq!(&line[start..end], "cafe\u{301}");
+
+        // NFKC folds compatibility variants such as the "ﬁ" ligature
+        let searcher = SearchOptions::new("file")
+            .normalization(Normalization::Nfkc)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.search("a \u{FB01}le").len(), 1);
+    }
+
+    #[test]
+    fn test_search_ignore_accents() {
+        let contents = "Crème brûlée\nCreme brulee\nCRÈME BRÛLÉE";
+
+        let searcher = SearchOptions::new("creme brulee")
+            .case_mode(CaseMode::Insensitive)
+            .ignore_accents(true)
+            .build()
+            .unwrap();
+        assert_eq!(searcher.search(contents).len(), 3);
+
+        // Regex queries are matched against the folded line too
+        let searcher = SearchOptions::new("br[u]l.e")
+            .ignore_accents(true)
+            .build()
+            .unwrap();
+        let results = searcher.search(contents);
+        assert_eq!(results.len(), 2);
+        let line = results[0].get_line_content();
+        let (start, end) = results[0].get_match_spans()[0];
+        assert_eq!(&line[start..end], "brûlée");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_literal_patterns_match_like_contains(
+            line in "[abc ]{0,30}",
+            patterns in prop::collection::vec("[abc]{1,3}", 1..20),
+        ) {
+            let searcher = SearchOptions::new("")
+                .patterns(patterns.clone())
+                .build()
+                .unwrap();
+
+            let expected: Vec<usize> = (0..patterns.len())
+                .filter(|&index| line.contains(patterns[index].as_str()))
+                .collect();
+            let results = searcher.search(&line);
+
+            if expected.is_empty() {
+                prop_assert!(results.is_empty());
+            } else {
+                prop_assert_eq!(results[0].get_matching_patterns(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn prop_case_insensitive_spans_are_valid(
+            line in "\\PC{0,40}",
+            pattern in "\\PC{1,5}",
+        ) {
+            let searcher = SearchOptions::new(&pattern)
+                .pattern_mode(PatternMode::Literal)
+                .case_mode(CaseMode::Insensitive)
+                .build()
+                .unwrap();
+
+            for result in searcher.search(&line) {
+                for &(start, end) in result.get_match_spans() {
+                    prop_assert!(start < end && end <= line.len());
+                    prop_assert!(line.is_char_boundary(start) && line.is_char_boundary(end));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_case_changed_text_is_found(
+            prefix in "\\PC{0,10}",
+            word in "[\\PC&&[^|\\s]]{1,10}",
+            suffix in "\\PC{0,10}",
+        ) {
+            let line = format!("{prefix}{}{suffix}", word.to_uppercase());
+            // Some characters don't round-trip through uppercase (e.g. `ı` becomes `I`)
+            prop_assume!(fold_case(&line).contains(&fold_case(&word)));
+
+            let searcher = SearchOptions::new(&word)
+                .pattern_mode(PatternMode::Literal)
+                .case_mode(CaseMode::Insensitive)
+                .build()
+                .unwrap();
+
+            let results = searcher.search(&line);
+            prop_assert_eq!(results.len(), 1);
+
+            // Every highlighted span folds to text that contains the pattern
+            let highlighted: String = results[0]
+                .get_match_spans()
+                .iter()
+                .map(|&(start, end)| fold_case(&line[start..end]))
+                .collect();
+            prop_assert!(highlighted.contains(&fold_case(&word)));
+        }
+    }
 }
@@ -6,17 +6,21 @@ use std::{env, process};
 /// Program entry point.
 ///
 /// Parses command-line arguments, configures and runs the search operation,
-/// and handles any errors that occur.
+/// and exits with a grep-compatible status: 0 if something matched, 1 if
+/// nothing matched and 2 if an error occurred.
 fn main() {
     let config = config::parse_args(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {err}");
         println!("\nFor help, use --help or -h");
-        process::exit(1);
+        process::exit(2);
     });
 
-    if let Err(e) = minigrep::run(config) {
-        eprintln!("Application error: {e}");
-        println!("\nTo see available options, use --help or -h");
-        process::exit(1);
+    match minigrep::run(config) {
+        Ok(outcome) => process::exit(outcome.exit_code()),
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            println!("\nTo see available options, use --help or -h");
+            process::exit(2);
+        }
     }
 }
@@ -1,75 +1,134 @@
 //! Contains configuration models and parsing logic for minigrep.
 
-use std::fmt;
+use crate::models::{
+    CaseMode, MatchBoundary, Normalization, PatternMode, SearchOptions, TableColumn, TableFormat,
+};
 
 /// Configuration for a minigrep search operation.
 ///
 /// Holds all the parameters that control the search behavior,
 /// including query patterns, file paths, and various options.
 pub struct Config {
-    pub query: String,
+    pub query: String,         // empty when all patterns are given with -e or -f
+    pub patterns: Vec<String>, // patterns from -e and -f, matched alongside the query
+    pub boolean_query: Option<String>, // --query expression, replaces the query and patterns
+    pub all_of: Vec<String>,   // files are only searched if all these patterns occur in them
+    pub none_of: Vec<String>,  // files are skipped if any of these patterns occur in them
+    pub near: Option<usize>,   // all patterns must occur within this many lines
+    pub multiline: bool,       // match against the whole file so matches can span lines
+    pub multiline_dotall: bool, // in multiline mode, `.` also matches line breaks
+    pub fuzzy: Option<usize>,  // match literally, allowing up to this many edits
+    pub replace: Option<String>, // print matching lines with the matches replaced
+    pub in_place: bool,        // write the replacements back to the files
+    pub backup: bool,          // keep a .bak copy of every rewritten file
+    pub dry_run: bool,         // show a diff of the rewrites instead of writing them
+    pub crlf: bool,            // `$` in regex patterns matches before `\r\n`
+    pub null_data: bool,       // input records end with NUL instead of a line break
+    pub record_separator: Option<String>, // regex separating records, which replace lines
+    pub json_fields: Vec<String>, // predicates on the fields of JSON lines, like level=error
+    pub table_format: Option<TableFormat>, // read CSV or TSV rows instead of lines
+    pub table_column: Option<TableColumn>, // the table column the query is matched within
     pub file_path_1: String,
     pub file_path_2: String, // can be an empty string or contain the second file name
-    pub ignore_case: bool,
-    pub context_flag: ContextFlag,
-    pub context_count: u8,
+    pub pattern_mode: PatternMode,
+    pub case_mode: CaseMode,
+    pub invert: bool,
+    pub boundary: MatchBoundary,
+    pub normalization: Normalization,
+    pub ignore_accents: bool,
+    pub context: ContextSpec,
+    pub group_separator: Option<String>, // printed between non-adjacent context groups
     pub show_stats: bool,
     pub recursive: bool,
+    pub output_mode: OutputMode,
+    pub quiet: bool,
+    pub max_count: Option<usize>, // stop after N matching lines in each file
+    pub max_total: Option<usize>, // stop the whole search after N matching lines
+    pub only_matching: bool,
+    pub line_number: bool,
+    pub with_filename: bool,
+    pub column: bool,      // show the column of the first match on each line
+    pub byte_offset: bool, // show the byte offset of each line, or of each match with -o
+    pub preserve_line_endings: bool, // end printed lines with their original terminators
+    pub null: bool,        // end printed file names with NUL
+    pub fields: Vec<String>, // fields of JSON lines to print instead of the whole line
+    pub pretty: bool,      // pretty-print JSON lines
+    pub csv_output: bool,  // print the header and matching rows of a table as plain CSV/TSV
+    pub json: bool,        // print each result as a JSON object, one per line
 }
 
-/// Represents the context display mode for search results.
-///
-/// Determines how many lines before and/or after a match are displayed.
-#[derive(PartialEq)]
-pub enum ContextFlag {
-    /// Display lines before matches
-    Before,
-    /// Display lines after matches
-    After,
-    /// Display lines before and after matches
-    Context,
-    /// Display search statistics
-    Stats,
+impl Config {
+    /// Returns the search options described by this configuration.
+    pub fn search_options(&self) -> SearchOptions {
+        SearchOptions::new(&self.query)
+            .patterns(self.patterns.clone())
+            .boolean_query(self.boolean_query.clone())
+            .all_of(self.all_of.clone())
+            .none_of(self.none_of.clone())
+            .near(self.near)
+            .multiline(self.multiline)
+            .multiline_dotall(self.multiline_dotall)
+            .fuzzy(self.fuzzy)
+            .replace(self.replace.clone())
+            .crlf(self.crlf)
+            .null_data(self.null_data)
+            .record_separator(self.record_separator.clone())
+            .json_fields(self.json_fields.clone())
+            .table_format(self.table_format)
+            .table_column(self.table_column.clone())
+            .pattern_mode(self.pattern_mode)
+            .case_mode(self.case_mode)
+            .context(self.context)
+            .invert(self.invert)
+            .boundary(self.boundary)
+            .max_count(self.max_count)
+            .normalization(self.normalization)
+            .ignore_accents(self.ignore_accents)
+    }
 }
 
-impl ContextFlag {
-    /// Creates a new ContextFlag from a string representation.
+/// Represents what is printed for each searched file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    /// Print matching lines (and any context lines)
+    Lines,
+    /// Print the number of matching lines per file
+    Count,
+    /// Print the number of individual matches per file
+    CountMatches,
+    /// Print only the names of files containing a match
+    FilesWithMatches,
+    /// Print only the names of files without any match
+    FilesWithoutMatch,
+}
+
+/// Number of context lines to display before and after each match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContextSpec {
+    /// Lines to display before each match
+    pub before: usize,
+    /// Lines to display after each match
+    pub after: usize,
+}
+
+impl ContextSpec {
+    /// Creates a new ContextSpec with independent before and after counts.
     ///
     /// # Arguments
     ///
-    /// * `flag` - A string that should be one of: "before", "after", "context", or "stats"
-    ///
-    /// # Panics
-    ///
-    /// Panics if the string doesn't match any of the valid flag values.
-    pub fn new(flag: &str) -> Self {
-        match flag {
-            "before" => Self::Before,
-            "after" => Self::After,
-            "context" => Self::Context,
-            "stats" => Self::Stats,
-            _ => panic!("Invalid context flag"),
-        }
+    /// * `before` - Number of lines to display before each match
+    /// * `after` - Number of lines to display after each match
+    pub fn new(before: usize, after: usize) -> Self {
+        Self { before, after }
     }
 
-    /// Returns the string representation of this ContextFlag.
-    pub fn as_str(&self) -> &str {
-        match self {
-            ContextFlag::Before => "before",
-            ContextFlag::After => "after",
-            ContextFlag::Context => "context",
-            ContextFlag::Stats => "stats",
-        }
+    /// Creates a ContextSpec with the same number of lines before and after.
+    pub fn symmetric(count: usize) -> Self {
+        Self::new(count, count)
     }
-}
 
-impl fmt::Display for ContextFlag {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ContextFlag::Before => write!(f, "before"),
-            ContextFlag::After => write!(f, "after"),
-            ContextFlag::Context => write!(f, "context"),
-            ContextFlag::Stats => write!(f, "stats"),
-        }
+    /// Returns true if no context lines are displayed.
+    pub fn is_empty(&self) -> bool {
+        self.before == 0 && self.after == 0
     }
 }
@@ -14,7 +14,22 @@ pub enum ConfigError {
     InvalidContextFlag(String),
     InvalidContextCount(String),
     InvalidArgument(String),
+    InvalidMaxCount(String),
+    InvalidNormalization(String),
+    InvalidNearDistance(String),
+    InvalidFuzzyDistance(String),
+    FuzzyDistanceTooLarge(usize, String),
+    MissingValue(String),
+    NoPatterns,
+    RequiresPattern(String),
+    ConflictingQuery,
+    UnreadablePatternFile(String, String),
     NotADirectory(String),
+    InPlaceWithoutReplace,
+    RequiresInPlace(String),
+    ConflictingFlags(String, String),
+    InvalidColumnIndex(String),
+    RequiresTableFormat(String),
 }
 
 impl fmt::Display for ConfigError {
@@ -28,9 +43,57 @@ impl fmt::Display for ConfigError {
                 write!(f, "Invalid context count: '{}'", count)
             }
             ConfigError::InvalidArgument(arg) => write!(f, "Invalid argument: '{}'", arg),
+            ConfigError::InvalidMaxCount(count) => write!(f, "Invalid max count: '{}'", count),
+            ConfigError::InvalidNormalization(form) => {
+                write!(
+                    f,
+                    "Invalid normalization form: '{}' (expected nfc or nfkc)",
+                    form
+                )
+            }
+            ConfigError::InvalidNearDistance(distance) => {
+                write!(f, "Invalid proximity distance: '{}'", distance)
+            }
+            ConfigError::InvalidFuzzyDistance(distance) => {
+                write!(f, "Invalid edit distance: '{}'", distance)
+            }
+            ConfigError::FuzzyDistanceTooLarge(distance, pattern) => write!(
+                f,
+                "Edit distance {} must be smaller than the length of '{}'",
+                distance, pattern
+            ),
+            ConfigError::MissingValue(flag) => write!(f, "Missing value for '{}'", flag),
+            ConfigError::NoPatterns => write!(f, "No patterns to search for"),
+            ConfigError::RequiresPattern(flag) => {
+                write!(f, "'{}' needs a PATTERN, -e or --query to search for", flag)
+            }
+            ConfigError::ConflictingQuery => {
+                write!(
+                    f,
+                    "--query can't be combined with a PATTERN argument, -e or -f"
+                )
+            }
+            ConfigError::UnreadablePatternFile(path, reason) => {
+                write!(f, "Cannot read pattern file '{}': {}", path, reason)
+            }
             ConfigError::NotADirectory(path) => {
                 write!(f, "Path provided is not a directory: '{}'", path)
             }
+            ConfigError::InPlaceWithoutReplace => {
+                write!(f, "'--in-place' needs a '--replace' template")
+            }
+            ConfigError::RequiresInPlace(flag) => {
+                write!(f, "'{}' can only be used with '--in-place'", flag)
+            }
+            ConfigError::ConflictingFlags(flag, other) => {
+                write!(f, "'{}' can't be combined with '{}'", flag, other)
+            }
+            ConfigError::InvalidColumnIndex(index) => {
+                write!(f, "Invalid column index: '{}'", index)
+            }
+            ConfigError::RequiresTableFormat(flag) => {
+                write!(f, "'{}' can only be used with '--csv' or '--tsv'", flag)
+            }
         }
     }
 }
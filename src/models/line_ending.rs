@@ -0,0 +1,89 @@
+//! Defines line terminators and the line-ending style of whole files.
+
+use std::fmt;
+
+/// The terminator that ends a single line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// A Unix line ending, `\n`
+    #[default]
+    Lf,
+    /// A Windows line ending, `\r\n`
+    CrLf,
+    /// The end of a NUL-terminated record, in null data
+    Nul,
+    /// The last line of a file without a final line break
+    None,
+}
+
+impl LineEnding {
+    /// Identifies a terminator from the text that follows a line's content.
+    ///
+    /// Any other terminator, such as a match of a record separator, is taken
+    /// as `\n`.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminator` - The text between the end of the line and the start of the next one
+    pub fn from_terminator(terminator: &str) -> Self {
+        match terminator {
+            "\r\n" => LineEnding::CrLf,
+            "\0" => LineEnding::Nul,
+            "" => LineEnding::None,
+            _ => LineEnding::Lf,
+        }
+    }
+
+    /// Returns the terminator as text.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Nul => "\0",
+            LineEnding::None => "",
+        }
+    }
+}
+
+/// The line endings used throughout a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LineEndingStyle {
+    /// Every line ends with `\n`
+    Lf,
+    /// Every line ends with `\r\n`
+    CrLf,
+    /// Some lines end with `\n` and others with `\r\n`
+    Mixed,
+    /// The file has no line breaks at all
+    None,
+}
+
+impl LineEndingStyle {
+    /// Detects the line-ending style of a file's contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The text content of the file
+    pub fn detect(contents: &str) -> Self {
+        let line_breaks = contents.matches('\n').count();
+        let crlf_breaks = contents.matches("\r\n").count();
+
+        match (line_breaks, crlf_breaks) {
+            (0, _) => LineEndingStyle::None,
+            (all, crlf) if all == crlf => LineEndingStyle::CrLf,
+            (_, 0) => LineEndingStyle::Lf,
+            _ => LineEndingStyle::Mixed,
+        }
+    }
+}
+
+impl fmt::Display for LineEndingStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineEndingStyle::Lf => write!(f, "LF"),
+            LineEndingStyle::CrLf => write!(f, "CRLF"),
+            LineEndingStyle::Mixed => write!(f, "mixed"),
+            LineEndingStyle::None => write!(f, "no line breaks"),
+        }
+    }
+}
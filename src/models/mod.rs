@@ -1,9 +1,17 @@
 mod config;
 mod error;
+mod line_ending;
+mod search_options;
+mod search_outcome;
 mod search_result;
 mod search_stat;
+mod table;
 
-pub use config::{Config, ContextFlag};
+pub use config::{Config, ContextSpec, OutputMode};
 pub use error::ConfigError;
+pub use line_ending::{LineEnding, LineEndingStyle};
+pub use search_options::{CaseMode, MatchBoundary, Normalization, PatternMode, SearchOptions};
+pub use search_outcome::SearchOutcome;
 pub use search_result::SearchResult;
 pub use search_stat::SearchStats;
+pub use table::{TableColumn, TableFormat};
@@ -0,0 +1,346 @@
+//! Defines the options that control how a search matches lines.
+
+use crate::{
+    core::Searcher,
+    models::{ContextSpec, TableColumn, TableFormat},
+};
+use std::error;
+
+/// How the query is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatternMode {
+    /// Treat the query as a regex if it contains regex metacharacters,
+    /// otherwise as literal patterns separated by `|`
+    #[default]
+    Auto,
+    /// Treat the query as literal patterns separated by `|`
+    Literal,
+    /// Treat the query as a regular expression
+    Regex,
+}
+
+/// How letter case is handled when matching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseMode {
+    /// Letters must match exactly
+    #[default]
+    Sensitive,
+    /// Letters match regardless of case
+    Insensitive,
+    /// Letters match regardless of case unless the pattern contains an
+    /// uppercase letter (decided per `|`-separated literal pattern)
+    Smart,
+}
+
+/// Where a match must start and end within a line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchBoundary {
+    /// Matches may appear anywhere in a line
+    #[default]
+    None,
+    /// Matches must be whole words
+    Word,
+    /// Matches must span the whole line
+    Line,
+}
+
+/// Unicode normalization applied to the query and each line before matching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Text is matched as it is
+    #[default]
+    None,
+    /// Canonical composition, so precomposed and decomposed accents match
+    Nfc,
+    /// Compatibility composition, which also folds variants such as ligatures
+    /// and full-width letters
+    Nfkc,
+}
+
+/// Options for a search, built up with chained setters.
+///
+/// # Examples
+///
+/// ```
+/// use minigrep::{CaseMode, ContextSpec, SearchOptions};
+///
+/// let searcher = SearchOptions::new("frog")
+///     .case_mode(CaseMode::Insensitive)
+///     .context(ContextSpec::new(1, 0))
+///     .build()
+///     .unwrap();
+///
+/// let results = searcher.search("How dreary\nHow public, like a Frog");
+/// assert_eq!(results.len(), 2);
+/// assert!(results[0].is_context());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchOptions {
+    pub query: String,
+    /// Extra patterns matched alongside the query, never split on `|`
+    pub patterns: Vec<String>,
+    pub pattern_mode: PatternMode,
+    pub case_mode: CaseMode,
+    pub context: ContextSpec,
+    pub invert: bool,
+    pub boundary: MatchBoundary,
+    pub max_count: Option<usize>,
+    pub normalization: Normalization,
+    pub ignore_accents: bool,
+    /// A boolean query like `ERROR AND NOT retry`, replacing the query and
+    /// extra patterns
+    pub boolean_query: Option<String>,
+    /// Patterns that must each occur somewhere in a file for it to be searched
+    pub all_of: Vec<String>,
+    /// Patterns that exclude a file from the search if they occur anywhere in it
+    pub none_of: Vec<String>,
+    /// Only match where every pattern occurs within this many lines
+    pub near: Option<usize>,
+    /// Match patterns against the whole content, so matches can span lines
+    pub multiline: bool,
+    /// Let `.` match line breaks in multiline mode
+    pub multiline_dotall: bool,
+    /// Match patterns as literals allowing up to this many edits
+    pub fuzzy: Option<usize>,
+    /// A template that every match in the results is replaced with
+    pub replace: Option<String>,
+    /// Let `$` match before the `\r` of a `\r\n` line ending
+    pub crlf: bool,
+    /// Split the content into NUL-terminated records instead of lines
+    pub null_data: bool,
+    /// A regex separating the records of the content, which then take the
+    /// place of lines
+    pub record_separator: Option<String>,
+    /// Predicates like `level=error` on the fields of JSON lines, which a
+    /// line must all satisfy besides matching the query
+    pub json_fields: Vec<String>,
+    /// Read the content as a delimited table, one row per record
+    pub table_format: Option<TableFormat>,
+    /// The column of the table the query is matched within
+    pub table_column: Option<TableColumn>,
+}
+
+impl SearchOptions {
+    /// Creates search options for a query with all other options at their defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The pattern to search for
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets extra patterns to match alongside the query.
+    ///
+    /// A line matches if any pattern matches. Unlike the query, extra
+    /// patterns are never split on `|`, and the query may be left empty when
+    /// all patterns are given this way.
+    pub fn patterns(mut self, patterns: Vec<String>) -> Self {
+        self.patterns = patterns;
+        self
+    }
+
+    /// Sets how the query is interpreted.
+    pub fn pattern_mode(mut self, pattern_mode: PatternMode) -> Self {
+        self.pattern_mode = pattern_mode;
+        self
+    }
+
+    /// Sets how letter case is handled.
+    pub fn case_mode(mut self, case_mode: CaseMode) -> Self {
+        self.case_mode = case_mode;
+        self
+    }
+
+    /// Sets the number of context lines around each match.
+    pub fn context(mut self, context: ContextSpec) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Selects lines that do not match instead of lines that do.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Sets where a match must start and end within a line.
+    pub fn boundary(mut self, boundary: MatchBoundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Sets the maximum number of matching lines to return per search.
+    pub fn max_count(mut self, max_count: Option<usize>) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    /// Sets the Unicode normalization applied before matching.
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Ignores accents on letters, so that `cafe` matches `café`.
+    pub fn ignore_accents(mut self, ignore_accents: bool) -> Self {
+        self.ignore_accents = ignore_accents;
+        self
+    }
+
+    /// Sets a boolean query that combines terms with `AND`, `OR` and `NOT`.
+    ///
+    /// When set, the query and extra patterns are ignored. Terms are bare
+    /// words or quoted phrases and can be grouped with parentheses.
+    pub fn boolean_query(mut self, boolean_query: Option<String>) -> Self {
+        self.boolean_query = boolean_query;
+        self
+    }
+
+    /// Sets patterns that must each occur somewhere in a file, not
+    /// necessarily on the same line, for [`Searcher::accepts_file`] to
+    /// accept it.
+    pub fn all_of(mut self, all_of: Vec<String>) -> Self {
+        self.all_of = all_of;
+        self
+    }
+
+    /// Sets patterns that make [`Searcher::accepts_file`] reject a file if
+    /// any of them occurs anywhere in it.
+    pub fn none_of(mut self, none_of: Vec<String>) -> Self {
+        self.none_of = none_of;
+        self
+    }
+
+    /// Matches only where every pattern occurs within a window of
+    /// `distance` lines.
+    ///
+    /// Each window is returned as a group: the lines with a pattern are
+    /// matches and the lines between them are context. Needs at least two
+    /// patterns. With a boolean query, the terms outside of any `NOT` must
+    /// occur in the window and the whole query must hold for it.
+    pub fn near(mut self, near: Option<usize>) -> Self {
+        self.near = near;
+        self
+    }
+
+    /// Matches the patterns against the whole content instead of line by
+    /// line, so a match can span several lines.
+    ///
+    /// Every line a match covers is a matching line, highlighted where the
+    /// match covers it. `^` and `$` match at the start and end of each line.
+    /// Can't be combined with a boolean query or proximity search.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Lets `.` match line breaks in multiline mode.
+    pub fn multiline_dotall(mut self, multiline_dotall: bool) -> Self {
+        self.multiline_dotall = multiline_dotall;
+        self
+    }
+
+    /// Matches patterns approximately, allowing up to `max_distance`
+    /// inserted, deleted or substituted characters.
+    ///
+    /// Patterns are always taken literally. On each matching line, the
+    /// substring closest to each pattern is highlighted, and the smallest
+    /// edit distance is recorded in the [`SearchResult`](crate::SearchResult).
+    pub fn fuzzy(mut self, max_distance: Option<usize>) -> Self {
+        self.fuzzy = max_distance;
+        self
+    }
+
+    /// Replaces every match in the matching lines of the results with a
+    /// template.
+    ///
+    /// In regex patterns, `$1` and `${name}` refer to capture groups, and
+    /// `$0` is the whole match for every pattern. Use `$$` for a literal `$`.
+    /// The match spans of the results then cover the replacements, and files
+    /// are never modified.
+    pub fn replace(mut self, template: Option<String>) -> Self {
+        self.replace = template;
+        self
+    }
+
+    /// Treats `\r\n` as a line ending in regex patterns, so `$` matches
+    /// before the `\r` and `.` never matches it.
+    ///
+    /// Lines are always split on both `\n` and `\r\n`, so this matters in
+    /// multiline mode, where the `\r` is part of the searched text.
+    pub fn crlf(mut self, crlf: bool) -> Self {
+        self.crlf = crlf;
+        self
+    }
+
+    /// Splits the content into records terminated by NUL instead of lines,
+    /// so that records may contain line breaks.
+    ///
+    /// Records then take the place of lines everywhere, including line
+    /// numbers and context.
+    pub fn null_data(mut self, null_data: bool) -> Self {
+        self.null_data = null_data;
+        self
+    }
+
+    /// Splits the content into records separated by matches of a regex
+    /// instead of lines, such as `\n---\n` or blank lines.
+    ///
+    /// Each record is matched and returned as a whole, and records take the
+    /// place of lines everywhere, including line numbers and context.
+    pub fn record_separator(mut self, separator: Option<String>) -> Self {
+        self.record_separator = separator;
+        self
+    }
+
+    /// Sets predicates on the fields of lines holding JSON objects, such as
+    /// `level=error`, `msg~timeout` or `latency_ms>=500`.
+    ///
+    /// A line matches only if it parses as JSON, satisfies every predicate
+    /// and matches the query, which may be left empty to select lines by
+    /// their fields alone.
+    pub fn json_fields(mut self, json_fields: Vec<String>) -> Self {
+        self.json_fields = json_fields;
+        self
+    }
+
+    /// Reads the content as a CSV or TSV table instead of lines.
+    ///
+    /// Each row is a record, including rows spanning several lines through
+    /// quoted line breaks. The first row is the header: it is never matched,
+    /// and is returned as context ahead of the matching rows.
+    pub fn table_format(mut self, table_format: Option<TableFormat>) -> Self {
+        self.table_format = table_format;
+        self
+    }
+
+    /// Only matches the query within one column of a table.
+    ///
+    /// Rows too short to have the column never match, and neither does any
+    /// row of content whose header lacks a column of that name.
+    pub fn table_column(mut self, table_column: Option<TableColumn>) -> Self {
+        self.table_column = table_column;
+        self
+    }
+
+    /// Compiles these options into a searcher.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The query or record separator contains an invalid regex pattern
+    /// - The boolean query or a JSON field predicate can't be parsed
+    /// - A proximity search has fewer than two patterns
+    /// - Multiline matching is combined with a boolean query, proximity or
+    ///   fuzzy search, replacement, JSON field predicates or a table
+    /// - Proximity search is combined with JSON field predicates
+    /// - A table is combined with null data or a record separator, or a table
+    ///   column is given without a table format
+    pub fn build(self) -> Result<Searcher, Box<dyn error::Error>> {
+        Searcher::new(self)
+    }
+}
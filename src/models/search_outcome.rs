@@ -0,0 +1,39 @@
+//! Defines the overall outcome of a search, used to pick the process exit code.
+
+/// The outcome of a search over one or more files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// At least one match was found
+    Matched,
+    /// No matches were found
+    NoMatch,
+    /// At least one file could not be searched (the error was already reported)
+    Failed,
+}
+
+impl SearchOutcome {
+    /// Combines the outcomes of two searches.
+    ///
+    /// Failures take precedence over matches, and matches take precedence
+    /// over no matches.
+    pub fn combine(self, other: SearchOutcome) -> SearchOutcome {
+        match (self, other) {
+            (SearchOutcome::Failed, _) | (_, SearchOutcome::Failed) => SearchOutcome::Failed,
+            (SearchOutcome::Matched, _) | (_, SearchOutcome::Matched) => SearchOutcome::Matched,
+            _ => SearchOutcome::NoMatch,
+        }
+    }
+
+    /// Returns the grep-compatible process exit code for this outcome.
+    ///
+    /// * `0` - Something matched
+    /// * `1` - Nothing matched
+    /// * `2` - An error occurred
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SearchOutcome::Matched => 0,
+            SearchOutcome::NoMatch => 1,
+            SearchOutcome::Failed => 2,
+        }
+    }
+}
@@ -1,14 +1,25 @@
 //! Defines data structures for storing and displaying search results.
 
-/// Represents a single matched line of text from a search operation.
+use crate::models::LineEnding;
+
+/// Represents a single line of text from a search operation.
 ///
 /// Contains the line content, its line number in the original file,
-/// and the specific patterns that matched within this line.
+/// the indices of the patterns that matched within this line, where the matches
+/// are, and whether the line is only included as context around a match.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     line_number: usize,
     line_content: String,
-    matching_patterns: Vec<String>,
+    matching_patterns: Vec<usize>,
+    match_spans: Vec<(usize, usize)>,
+    is_context: bool,
+    edit_distances: Vec<usize>,
+    byte_offset: usize,
+    line_ending: LineEnding,
+    /// The line before its matches were replaced, with the spans of the
+    /// replaced matches in it
+    original: Option<(String, Vec<(usize, usize)>)>,
 }
 
 impl SearchResult {
@@ -18,20 +29,103 @@ impl SearchResult {
     ///
     /// * `line_number` - Zero-based line number where the match was found
     /// * `line_content` - The full text of the matching line
-    /// * `matching_patterns` - The patterns that matched on this line
-    pub fn new(line_number: usize, line_content: String, matching_patterns: Vec<String>) -> Self {
+    /// * `matching_patterns` - Indices in [`crate::core::Searcher::patterns`] of the
+    ///   patterns that matched on this line, in order
+    /// * `match_spans` - Sorted `(start, end)` byte offsets of the matches in the line
+    pub fn new(
+        line_number: usize,
+        line_content: String,
+        matching_patterns: Vec<usize>,
+        match_spans: Vec<(usize, usize)>,
+    ) -> Self {
         Self {
             line_number,
             line_content,
             matching_patterns,
+            match_spans,
+            is_context: false,
+            edit_distances: Vec::new(),
+            byte_offset: 0,
+            line_ending: LineEnding::default(),
+            original: None,
+        }
+    }
+
+    /// Records the line as it was before its matches were replaced, so
+    /// columns and byte offsets refer to the searched content.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_content` - The full text of the line before replacement
+    /// * `match_spans` - The `(start, end)` byte offsets of the replaced matches in
+    ///   that text, one for each match span
+    pub fn with_original(mut self, line_content: String, match_spans: Vec<(usize, usize)>) -> Self {
+        self.original = Some((line_content, match_spans));
+        self
+    }
+
+    /// Records the edit distance of each fuzzy match on this line.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit_distances` - The edit distance of every match, in the order of the match spans
+    pub fn with_edit_distances(mut self, edit_distances: Vec<usize>) -> Self {
+        self.edit_distances = edit_distances;
+        self
+    }
+
+    /// Creates a new SearchResult for a line included only as context.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_number` - Zero-based line number of the context line
+    /// * `line_content` - The full text of the context line
+    pub fn context(line_number: usize, line_content: String) -> Self {
+        Self {
+            line_number,
+            line_content,
+            matching_patterns: Vec::new(),
+            match_spans: Vec::new(),
+            is_context: true,
+            edit_distances: Vec::new(),
+            byte_offset: 0,
+            line_ending: LineEnding::default(),
+            original: None,
         }
     }
 
-    /// Returns the patterns that matched on this line.
-    pub fn get_matching_patterns(&self) -> &[String] {
+    /// Records where this line starts in the searched content.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte_offset` - Byte offset of the start of the line, counting the
+    ///   full terminators of the lines before it
+    pub fn with_byte_offset(mut self, byte_offset: usize) -> Self {
+        self.byte_offset = byte_offset;
+        self
+    }
+
+    /// Records the terminator this line ended with in the searched content.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_ending` - The line's original terminator
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Returns the indices of the patterns that matched on this line, in the
+    /// order of [`crate::core::Searcher::patterns`].
+    pub fn get_matching_patterns(&self) -> &[usize] {
         &self.matching_patterns
     }
 
+    /// Returns the `(start, end)` byte offsets of the matches in this line.
+    pub fn get_match_spans(&self) -> &[(usize, usize)] {
+        &self.match_spans
+    }
+
     /// Returns the zero-based line number of this result.
     pub fn get_line_number(&self) -> usize {
         self.line_number
@@ -41,4 +135,53 @@ impl SearchResult {
     pub fn get_line_content(&self) -> &str {
         &self.line_content
     }
+
+    /// Returns the edit distance of each match on this line, in the order of
+    /// the match spans, or nothing outside of fuzzy matching.
+    pub fn get_edit_distances(&self) -> &[usize] {
+        &self.edit_distances
+    }
+
+    /// Returns the byte offset of the start of this line in the searched content.
+    pub fn get_byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Returns the terminator this line ended with in the searched content.
+    pub fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Returns the one-based character column of the first match on this
+    /// line, or `None` if nothing on the line is highlighted.
+    ///
+    /// The column is where the match was before any replacement.
+    pub fn get_column(&self) -> Option<usize> {
+        self.get_match_positions()
+            .first()
+            .map(|&(column, _)| column)
+    }
+
+    /// Returns where each match was in the searched content, in the order
+    /// of the match spans.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(usize, usize)>` - The one-based character column and the byte offset
+    ///   in the line of the start of each match, before any replacement
+    pub fn get_match_positions(&self) -> Vec<(usize, usize)> {
+        let (line_content, match_spans) = match &self.original {
+            Some((line_content, match_spans)) => (line_content, match_spans),
+            None => (&self.line_content, &self.match_spans),
+        };
+        match_spans
+            .iter()
+            .map(|&(start, _)| (line_content[..start].chars().count() + 1, start))
+            .collect()
+    }
+
+    /// Returns true if this line is only included as context around a match.
+    pub fn is_context(&self) -> bool {
+        self.is_context
+    }
 }
@@ -1,134 +1,156 @@
 //! Provides functionality for tracking and displaying search statistics.
 
-use crate::models::{Config, SearchResult};
-use std::time::Duration;
+use crate::{
+    core::Searcher,
+    models::{LineEndingStyle, SearchResult},
+};
+use std::{collections::BTreeMap, time::Duration};
 
 /// Tracks statistics about a search operation.
 ///
 /// Collects metrics such as number of files searched, lines processed,
 /// matches found, and time taken for the search operation.
 pub struct SearchStats {
-    pub query: String,
+    /// Matching lines per pattern, indexed like [`Searcher::patterns`]
+    pub pattern_lines: Vec<(String, usize)>,
+    /// The JSON field predicates lines were selected by, as given
+    pub json_fields: Vec<String>,
     pub total_lines: usize,
     pub total_matches: usize,
     pub files_searched: usize,
+    pub files_matched: usize,
+    /// Files skipped because of `--all-of` or `--none-of`
+    pub files_filtered: usize,
+    /// Matches per edit distance, in fuzzy mode
+    pub distance_matches: BTreeMap<usize, usize>,
+    /// Files and lines changed by `--in-place`
+    pub files_rewritten: usize,
+    pub lines_rewritten: usize,
+    /// Whether rewrites were only previewed with `--dry-run`
+    pub dry_run: bool,
+    /// The line-ending style of every searched file, in search order
+    pub file_line_endings: Vec<(String, LineEndingStyle)>,
     pub duration: Duration,
 }
 
 impl SearchStats {
-    /// Initializes a new SearchStats instance from a Config.
+    /// Initializes a new SearchStats instance for a compiled search.
     ///
     /// # Arguments
     ///
-    /// * `config` - The search configuration to extract initial data from
-    pub fn init_stats(config: &Config) -> Self {
+    /// * `searcher` - The compiled search, whose patterns matches are attributed to
+    pub fn init_stats(searcher: &Searcher) -> Self {
         SearchStats {
-            query: config.query.clone(),
+            pattern_lines: searcher
+                .patterns()
+                .map(|pattern| (pattern.to_string(), 0))
+                .collect(),
+            json_fields: searcher.options().json_fields.clone(),
             total_lines: 0,
             total_matches: 0,
             files_searched: 0,
+            files_matched: 0,
+            files_filtered: 0,
+            distance_matches: BTreeMap::new(),
+            files_rewritten: 0,
+            lines_rewritten: 0,
+            dry_run: false,
+            file_line_endings: Vec::new(),
             duration: Duration::default(),
         }
     }
 
     /// Updates the match count based on search results.
     ///
+    /// Also counts the matching lines of every pattern that hit, and the
+    /// matches of every edit distance in fuzzy mode.
+    ///
     /// # Arguments
     ///
     /// * `results` - The search results to count matches from
-    /// * `config` - The search configuration used
-    pub fn update_match_count(&mut self, results: &[SearchResult], config: &Config) {
-        // Check if we're using regex patterns
-        let regex_indicators = [
-            '*', '+', '?', '.', '\\', '[', ']', '(', ')', '{', '}', '^', '$',
-        ];
-        let might_be_regex = config.query.chars().any(|c| regex_indicators.contains(&c));
+    pub fn update_match_count(&mut self, results: &[SearchResult]) {
+        for &pattern_index in results.iter().flat_map(SearchResult::get_matching_patterns) {
+            self.pattern_lines[pattern_index].1 += 1;
+        }
 
-        if might_be_regex {
-            for result in results {
-                // Only count lines with actual matches, not context lines
-                if !result.get_matching_patterns().is_empty() {
-                    let pattern = &result.get_matching_patterns()[0];
-                    let line = result.get_line_content();
+        for &distance in results.iter().flat_map(SearchResult::get_edit_distances) {
+            *self.distance_matches.entry(distance).or_default() += 1;
+        }
 
-                    // Try to compile the regex
-                    let regex_result = if config.ignore_case {
-                        regex::RegexBuilder::new(pattern)
-                            .case_insensitive(true)
-                            .build()
-                    } else {
-                        regex::Regex::new(pattern)
-                    };
+        self.total_matches += results
+            .iter()
+            // Only count lines with actual matches, not context lines
+            .filter(|result| !result.is_context())
+            // Lines selected by inverted matching have no spans but count as one match
+            .map(|result| result.get_match_spans().len().max(1))
+            .sum::<usize>();
+    }
 
-                    if let Ok(regex) = regex_result {
-                        // Count all regex matches in this line
-                        self.total_matches += regex.find_iter(line).count();
-                    }
-                }
-                // If result is empty, move on to the next result
-            }
-        } else {
-            // For regular patterns, use the existing count method
-            self.total_matches += count_actual_matches(results, config.ignore_case);
-        }
+    /// Records the line-ending style of a searched file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_label` - Name or path of the file
+    /// * `contents` - The text content of the file
+    pub fn record_line_endings(&mut self, file_label: &str, contents: &str) {
+        self.file_line_endings
+            .push((file_label.to_string(), LineEndingStyle::detect(contents)));
     }
 
     /// Displays the collected statistics to the console.
     pub fn display(&self) {
         println!("\n--- Search Statistics ---");
-        println!("Pattern searched: '{}'", self.query);
+        match self.pattern_lines.as_slice() {
+            // Field predicates without a pattern leave an empty one matching every line
+            [(pattern, _)] if pattern.is_empty() && !self.json_fields.is_empty() => {}
+            [(pattern, _)] => println!("Pattern searched: '{}'", pattern),
+            _ => {
+                println!("Patterns searched: {}", self.pattern_lines.len());
+                for (pattern, lines) in &self.pattern_lines {
+                    println!("  '{}': {} matching lines", pattern, lines);
+                }
+            }
+        }
+        if !self.json_fields.is_empty() {
+            println!("Field predicates: {}", self.json_fields.join(", "));
+        }
         println!("Files searched: {}", self.files_searched);
+        println!("Files with matches: {}", self.files_matched);
+        if self.files_filtered > 0 {
+            println!("Files filtered out: {}", self.files_filtered);
+        }
+        if !self.distance_matches.is_empty() {
+            println!("Matches by edit distance:");
+            for (distance, matches) in &self.distance_matches {
+                println!("  {}: {} matches", distance, matches);
+            }
+        }
+        if self.files_rewritten > 0 {
+            let action = if self.dry_run {
+                "to rewrite"
+            } else {
+                "rewritten"
+            };
+            println!("Files {}: {}", action, self.files_rewritten);
+            println!("Lines {}: {}", action, self.lines_rewritten);
+        }
+        if !self.file_line_endings.is_empty() {
+            let mut style_files: BTreeMap<LineEndingStyle, usize> = BTreeMap::new();
+            for (_, style) in &self.file_line_endings {
+                *style_files.entry(*style).or_default() += 1;
+            }
+            let styles: Vec<String> = style_files
+                .iter()
+                .map(|(style, files)| format!("{} {}", files, style))
+                .collect();
+            println!("Files by line ending: {}", styles.join(", "));
+            for (file_label, style) in &self.file_line_endings {
+                println!("  {}: {}", file_label, style);
+            }
+        }
         println!("Total lines searched: {}", self.total_lines);
         println!("Matches found: {}", self.total_matches);
         println!("Search completed in: {:.2?}", self.duration);
         println!("------------------------");
     }
 }
-
-/// Counts the total number of pattern matches across all search results.
-///
-/// This function performs a detailed count of all individual pattern matches,
-/// including multiple occurrences of the same pattern within a single line.
-///
-/// # Arguments
-///
-/// * `results` - A slice of SearchResult objects to count matches in
-/// * `ignore_case` - Whether to perform case-insensitive matching
-///
-/// # Returns
-///
-/// The total count of all pattern matches found
-fn count_actual_matches(results: &[SearchResult], ignore_case: bool) -> usize {
-    results
-        .iter()
-        .filter(|result| !result.get_matching_patterns().is_empty())
-        .flat_map(|result| {
-            let line = result.get_line_content();
-            result.get_matching_patterns().iter().map(move |pattern| {
-                let mut count = 0;
-                let mut start = 0;
-
-                let pattern_lower = if ignore_case {
-                    pattern.to_lowercase()
-                } else {
-                    pattern.clone()
-                };
-
-                let line_lower = if ignore_case {
-                    line.to_lowercase()
-                } else {
-                    line.to_string()
-                };
-
-                while let Some(position) = line_lower[start..].find(&pattern_lower) {
-                    count += 1;
-                    start += position + pattern_lower.len();
-                    if start >= line_lower.len() {
-                        break;
-                    }
-                }
-                count
-            })
-        })
-        .sum()
-}
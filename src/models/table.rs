@@ -0,0 +1,32 @@
+//! Defines delimited table formats and how a column of a table is chosen.
+
+/// A format of delimited rows whose first row is a header naming the columns.
+///
+/// Fields may be quoted with `"`, and quoted fields can contain the
+/// delimiter, line breaks and quotes written as `""`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Comma-separated values
+    Csv,
+    /// Tab-separated values
+    Tsv,
+}
+
+impl TableFormat {
+    /// Returns the ASCII character separating the fields of a row.
+    pub fn delimiter(&self) -> u8 {
+        match self {
+            TableFormat::Csv => b',',
+            TableFormat::Tsv => b'\t',
+        }
+    }
+}
+
+/// The column of a table that the query is matched within.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableColumn {
+    /// The column with this name in the header row
+    Name(String),
+    /// The column at this zero-based position
+    Index(usize),
+}
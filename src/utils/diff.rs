@@ -0,0 +1,100 @@
+//! Builds unified diffs of line replacements, for previewing file rewrites.
+
+use std::collections::BTreeMap;
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Builds a unified diff of a file in which some ranges of lines are replaced.
+///
+/// Changes closer together than twice the context share a hunk. A
+/// replacement can span any number of lines, so a multi-line record can be
+/// replaced as a whole.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file, used in the `---` and `+++` headers
+/// * `lines` - The original lines of the file
+/// * `changes` - The last zero-based line number and the replacement text of
+///   every changed range of lines, by its first line number
+///
+/// # Returns
+///
+/// * `String` - The diff, or an empty string if nothing changed
+pub fn unified_diff(
+    path: &str,
+    lines: &[&str],
+    changes: &BTreeMap<usize, (usize, String)>,
+) -> String {
+    let changed: Vec<(usize, usize)> = changes
+        .iter()
+        .map(|(&first, &(last, _))| (first, last))
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+    // Lines added by earlier hunks, which shifts where later hunks start in
+    // the new file
+    let mut added: isize = 0;
+    let mut hunk_start = 0;
+
+    while hunk_start < changed.len() {
+        // Extend the hunk while the next change is within reach of its context
+        let mut hunk_end = hunk_start;
+        while hunk_end + 1 < changed.len()
+            && changed[hunk_end + 1].0 - changed[hunk_end].1 <= 2 * CONTEXT_LINES + 1
+        {
+            hunk_end += 1;
+        }
+
+        let first = changed[hunk_start].0.saturating_sub(CONTEXT_LINES);
+        let last = (changed[hunk_end].1 + CONTEXT_LINES).min(lines.len() - 1);
+        let mut body = String::new();
+        let mut new_count = 0;
+        let mut line_number = first;
+
+        while line_number <= last {
+            if !changes.contains_key(&line_number) {
+                body.push_str(&format!(" {}\n", lines[line_number]));
+                new_count += 1;
+                line_number += 1;
+                continue;
+            }
+
+            // A run of adjacent changes is shown as all removals, then all additions
+            let mut run = Vec::new();
+            while let Some((run_last, replacement)) = changes.get(&line_number) {
+                run.push(replacement);
+                for removed in &lines[line_number..=*run_last] {
+                    body.push_str(&format!("-{removed}\n"));
+                }
+                line_number = run_last + 1;
+            }
+            for replacement in run {
+                for new_line in replacement.split('\n') {
+                    let new_line = new_line.strip_suffix('\r').unwrap_or(new_line);
+                    body.push_str(&format!("+{new_line}\n"));
+                    new_count += 1;
+                }
+            }
+        }
+
+        let old_count = last - first + 1;
+        let new_start = first as isize + added;
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            first + 1,
+            old_count,
+            new_start + 1,
+            new_count
+        ));
+        diff.push_str(&body);
+
+        added += new_count as isize - old_count as isize;
+        hunk_start = hunk_end + 1;
+    }
+
+    diff
+}
@@ -1,8 +1,11 @@
 //! Utility functions for the minigrep tool.
 
 use std::{
-    fs, io,
+    ffi::OsString,
+    fs,
+    io::{self, ErrorKind, Write},
     path::{Path, PathBuf},
+    process,
 };
 
 /// Recursively gathers all files in a directory.
@@ -57,6 +60,58 @@ pub fn get_all_files_in_directory(dir_path: &str) -> io::Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// Replaces the contents of a file without ever leaving it half written.
+///
+/// The new contents are written to a hidden temporary file next to the
+/// original, which then takes the original's place with a rename. The file
+/// keeps its permissions.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to rewrite
+/// * `contents` - The new contents of the file
+/// * `keep_backup` - Whether to keep a copy of the original as `<path>.bak`
+///
+/// # Errors
+///
+/// Returns an error if the file's metadata can't be read, or the temporary
+/// file or backup can't be written. The original file is left unchanged and
+/// the temporary file is removed.
+pub fn write_atomically(path: &Path, contents: &str, keep_backup: bool) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Not a file path"))?;
+    let permissions = fs::metadata(path)?.permissions();
+
+    // Hidden, so a recursive search never picks it up
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = (|| {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(&temp_path, permissions)?;
+
+        if keep_backup {
+            let mut backup_path = path.as_os_str().to_owned();
+            backup_path.push(".bak");
+            fs::copy(path, backup_path)?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
 fn is_hidden_file(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
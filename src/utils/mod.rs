@@ -1,3 +1,9 @@
+mod diff;
 mod file_system;
+mod table;
+mod text_fold;
 
-pub use file_system::get_all_files_in_directory;
+pub use diff::unified_diff;
+pub use file_system::{get_all_files_in_directory, write_atomically};
+pub use table::{table_fields, table_rows, unquote_field};
+pub use text_fold::{FoldedText, Folding};
@@ -0,0 +1,126 @@
+//! Splits delimited tables such as CSV into rows and fields.
+//!
+//! A field starting with `"` is quoted and can contain the delimiter and
+//! line breaks, with quotes written as `""`. Quotes anywhere else in a field
+//! are taken literally.
+
+/// Splits a table into rows, leaving line breaks inside quoted fields alone.
+///
+/// Rows end with `\n` or `\r\n` outside of quotes, and the terminators are
+/// left out. A final line break doesn't start another row.
+///
+/// # Arguments
+///
+/// * `contents` - The text content of the table
+/// * `delimiter` - The ASCII character separating the fields
+///
+/// # Returns
+///
+/// * `Vec<(usize, usize)>` - The `(start, end)` byte offsets of each row
+pub fn table_rows(contents: &str, delimiter: u8) -> Vec<(usize, usize)> {
+    let bytes = contents.as_bytes();
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut field_start = true;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' if field_start => {
+                // Jump to the closing quote, past any line breaks
+                index += 1 + closing_quote(&contents[index + 1..]);
+                field_start = false;
+            }
+            b'\n' => {
+                let end = if index > start && bytes[index - 1] == b'\r' {
+                    index - 1
+                } else {
+                    index
+                };
+                rows.push((start, end));
+                start = index + 1;
+                field_start = true;
+            }
+            byte => field_start = byte == delimiter,
+        }
+        index += 1;
+    }
+
+    if start < bytes.len() {
+        rows.push((start, bytes.len()));
+    }
+    rows
+}
+
+/// Finds the fields of a row.
+///
+/// The span of a quoted field covers only the text between its quotes, in
+/// which `""` is left as it is. Text between a closing quote and the next
+/// delimiter is ignored.
+///
+/// # Arguments
+///
+/// * `row` - The text of one row, without its terminator
+/// * `delimiter` - The ASCII character separating the fields
+///
+/// # Returns
+///
+/// * `Vec<(usize, usize)>` - The `(start, end)` byte offsets of each field in the row
+pub fn table_fields(row: &str, delimiter: u8) -> Vec<(usize, usize)> {
+    let bytes = row.as_bytes();
+    let mut fields = Vec::new();
+    let mut position = 0;
+
+    loop {
+        // The closing quote of a quoted field, after which the delimiter is looked for
+        let closing = (bytes.get(position) == Some(&b'"'))
+            .then(|| position + 1 + closing_quote(&row[position + 1..]));
+        let search_from = closing.unwrap_or(position);
+        let next = bytes[search_from..]
+            .iter()
+            .position(|&byte| byte == delimiter)
+            .map(|offset| search_from + offset);
+
+        fields.push(match closing {
+            Some(closing) => (position + 1, closing),
+            None => (position, next.unwrap_or(bytes.len())),
+        });
+
+        match next {
+            Some(next) => position = next + 1,
+            None => return fields,
+        }
+    }
+}
+
+/// Returns the text of a field with its `""` quotes unescaped.
+///
+/// # Arguments
+///
+/// * `field` - The text of a field, between its quotes if it has them
+pub fn unquote_field(field: &str) -> String {
+    field.replace("\"\"", "\"")
+}
+
+/// Returns the byte offset of the quote closing a quoted field, or the end
+/// of the text if the quote is missing.
+///
+/// # Arguments
+///
+/// * `quoted` - The text following the opening quote
+fn closing_quote(quoted: &str) -> usize {
+    let bytes = quoted.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'"' {
+            if bytes.get(index + 1) == Some(&b'"') {
+                index += 2;
+                continue;
+            }
+            return index;
+        }
+        index += 1;
+    }
+    bytes.len()
+}
@@ -0,0 +1,206 @@
+//! Case folding and Unicode normalization with offsets that map back to the
+//! original text.
+
+use crate::models::Normalization;
+use std::borrow::Cow;
+use unicode_normalization::{UnicodeNormalization, char::canonical_combining_class};
+
+/// The transformations applied to text before matching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Folding {
+    pub ignore_case: bool,
+    pub normalization: Normalization,
+    pub ignore_accents: bool,
+}
+
+impl Folding {
+    /// Returns the same folding with case folding switched on or off.
+    pub fn with_ignore_case(self, ignore_case: bool) -> Self {
+        Self {
+            ignore_case,
+            ..self
+        }
+    }
+
+    /// Returns true if this folding leaves every text unchanged.
+    pub fn is_identity(&self) -> bool {
+        !self.ignore_case && self.normalization == Normalization::None && !self.ignore_accents
+    }
+
+    /// Folds a string without tracking offsets, for use on patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to fold
+    pub fn apply(&self, text: &str) -> String {
+        FoldedText::new(text, *self).folded.into_owned()
+    }
+
+    /// Appends the folding of a segment of text.
+    ///
+    /// Normalization and accent removal work on the whole segment, since a
+    /// base character and its combining marks can compose into one character.
+    fn fold_segment(&self, segment: &str, folded: &mut String) {
+        let normalized: Cow<str> = match (self.normalization, self.ignore_accents) {
+            (Normalization::Nfkc, true) => segment.nfkd().filter(|&c| !is_accent(c)).collect(),
+            (_, true) => segment.nfd().filter(|&c| !is_accent(c)).collect(),
+            (Normalization::Nfc, false) => segment.nfc().collect(),
+            (Normalization::Nfkc, false) => segment.nfkc().collect(),
+            (Normalization::None, false) => Cow::Borrowed(segment),
+        };
+
+        if self.ignore_case {
+            normalized.chars().for_each(|c| fold_char(c, folded));
+        } else {
+            folded.push_str(&normalized);
+        }
+    }
+}
+
+/// Text that has been folded, remembering where each folded byte came from
+/// in the original text.
+///
+/// Folding can change the byte length of a string (`ẞ` folds to `ss`, `e` and
+/// a combining accent compose into `é`), so offsets found in the folded text
+/// can't be used on the original directly. [`FoldedText::original_span`]
+/// translates them.
+pub struct FoldedText<'a> {
+    folded: Cow<'a, str>,
+    // Byte offset in the original text of the segment each folded byte came
+    // from, or `None` when the offsets are unchanged
+    origins: Option<Vec<usize>>,
+    original_len: usize,
+}
+
+impl<'a> FoldedText<'a> {
+    /// Folds the text and records the origin of every folded byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The original text to fold
+    /// * `folding` - The transformations to apply
+    pub fn new(text: &'a str, folding: Folding) -> Self {
+        if folding.is_identity() {
+            return Self {
+                folded: Cow::Borrowed(text),
+                origins: None,
+                original_len: text.len(),
+            };
+        }
+
+        // ASCII text is already normalized and case folding never changes
+        // its byte lengths
+        if text.is_ascii() {
+            let folded = if folding.ignore_case {
+                Cow::Owned(text.to_ascii_lowercase())
+            } else {
+                Cow::Borrowed(text)
+            };
+            return Self {
+                folded,
+                origins: None,
+                original_len: text.len(),
+            };
+        }
+
+        let mut folded = String::with_capacity(text.len());
+        let mut origins = Vec::with_capacity(text.len());
+
+        for (offset, segment) in segments(text) {
+            let folded_start = folded.len();
+            folding.fold_segment(segment, &mut folded);
+            origins.resize(origins.len() + folded.len() - folded_start, offset);
+        }
+
+        Self {
+            folded: Cow::Owned(folded),
+            origins: Some(origins),
+            original_len: text.len(),
+        }
+    }
+
+    /// Returns the folded text.
+    pub fn as_str(&self) -> &str {
+        &self.folded
+    }
+
+    /// Maps a byte range of the folded text to a byte range of the original.
+    ///
+    /// A range that starts or ends inside the folding of a single original
+    /// segment is widened to cover that whole segment, so the result always
+    /// lies on character boundaries of the original text.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start byte offset in the folded text
+    /// * `end` - End byte offset in the folded text
+    pub fn original_span(&self, start: usize, end: usize) -> (usize, usize) {
+        let Some(origins) = &self.origins else {
+            return (start, end);
+        };
+
+        let original_start = origins.get(start).copied().unwrap_or(self.original_len);
+
+        // The end is where the first original segment after the range starts
+        let original_end = if end == 0 {
+            original_start
+        } else {
+            let last_origin = origins[end - 1];
+            origins[end..]
+                .iter()
+                .copied()
+                .find(|&origin| origin != last_origin)
+                .unwrap_or(self.original_len)
+        };
+
+        (original_start, original_end.max(original_start))
+    }
+}
+
+/// Splits text into segments of a starter character followed by any
+/// combining characters, returning each with its byte offset.
+///
+/// Hangul vowel and final consonant jamo stay with the preceding syllable
+/// because they compose with it.
+fn segments(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut starts = text
+        .char_indices()
+        .filter(|&(offset, c)| {
+            offset == 0
+                || (canonical_combining_class(c) == 0 && !('\u{1160}'..='\u{11FF}').contains(&c))
+        })
+        .map(|(offset, _)| offset)
+        .peekable();
+
+    std::iter::from_fn(move || {
+        let start = starts.next()?;
+        let end = starts.peek().copied().unwrap_or(text.len());
+        Some((start, &text[start..end]))
+    })
+}
+
+/// Returns true for combining diacritical marks, which are dropped when
+/// ignoring accents.
+fn is_accent(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+/// Appends the case folding of a single character.
+///
+/// Uses the lowercase mapping, plus the full case foldings that lowercase
+/// mapping misses, so that e.g. `STRASSE` and `straße` compare equal.
+fn fold_char(c: char, folded: &mut String) {
+    match c {
+        'ß' | 'ẞ' => folded.push_str("ss"),
+        'ς' => folded.push('σ'),
+        'ſ' => folded.push('s'),
+        _ => folded.extend(c.to_lowercase()),
+    }
+}
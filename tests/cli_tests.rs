@@ -58,9 +58,9 @@ mod integration_tests {
         let stdout = String::from_utf8(output.stdout).unwrap();
         let clean_stdout = strip_ansi_color_codes(&stdout);
 
-        assert!(clean_stdout.contains("Line 6: How dreary to be somebody!"));
+        assert!(clean_stdout.contains("Line 6- How dreary to be somebody!"));
         assert!(clean_stdout.contains("Line 7: How public, like a frog"));
-        assert!(clean_stdout.contains("Line 8: To tell your name the livelong day"));
+        assert!(clean_stdout.contains("Line 8- To tell your name the livelong day"));
     }
 
     #[test]
@@ -101,7 +101,7 @@ mod integration_tests {
         let stderr = String::from_utf8(output.stderr).unwrap();
 
         assert!(stderr.contains("Problem parsing arguments: Not enough arguments"));
-        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(output.status.code(), Some(2));
     }
 
     #[test]
@@ -119,6 +119,911 @@ mod integration_tests {
 
         assert!(stderr.contains("Application error:"));
         assert!(stderr.contains("No such file or directory found: 'nonexistent-file.txt'"));
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_only_matching_flag() {
+        let mut cmd = Command::cargo_bin("minigrep").unwrap();
+
+        // Run with only-matching and line-number flags
+        let output = cmd
+            .arg("nobody|frog")
+            .arg("tests/fixtures/poem.txt")
+            .arg("-o")
+            .arg("-n")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let clean_stdout = strip_ansi_color_codes(&stdout);
+
+        assert_eq!(clean_stdout, "1:nobody\n2:nobody\n7:frog\n");
+    }
+
+    #[test]
+    fn test_only_matching_regex_with_filename() {
+        let mut cmd = Command::cargo_bin("minigrep").unwrap();
+
+        // Run with only-matching and with-filename flags using a regex
+        let output = cmd
+            .arg("\\bs\\w+")
+            .arg("tests/fixtures/sunrise.txt")
+            .arg("--only-matching")
+            .arg("--with-filename")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let clean_stdout = strip_ansi_color_codes(&stdout);
+
+        assert_eq!(
+            clean_stdout,
+            "tests/fixtures/sunrise.txt:sun\n\
+             tests/fixtures/sunrise.txt:splash\n\
+             tests/fixtures/sunrise.txt:spills\n\
+             tests/fixtures/sunrise.txt:subtle\n\
+             tests/fixtures/sunrise.txt:start\n"
+        );
+    }
+
+    #[test]
+    fn test_count_flags() {
+        // Count matching lines
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["to", "tests/fixtures/poem.txt", "-c", "-i"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/poem.txt:4\n");
+
+        // Count individual matches, including repeats on the same line
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody", "tests/fixtures/poem.txt", "--count-matches"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/poem.txt:2\n");
+    }
+
+    #[test]
+    fn test_files_with_and_without_matches() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["gentle", "tests/fixtures", "-r", "-l"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/sunrise.txt\n");
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["gentle", "tests/fixtures", "-r", "-L"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut files: Vec<&str> = stdout.lines().collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["tests/fixtures/nightfall.txt", "tests/fixtures/poem.txt"]
+        );
+    }
+
+    #[test]
+    fn test_exit_status() {
+        // Match found
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(0));
+
+        // No match found
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["unicorn", "tests/fixtures/poem.txt"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(1));
+
+        // Invalid regex is an error
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog[", "tests/fixtures/poem.txt"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_quiet_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["gentle", "tests/fixtures", "-r", "-q", "--stats"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.stdout.is_empty());
+        assert_eq!(output.status.code(), Some(0));
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["unicorn", "tests/fixtures", "-r", "--quiet"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.stdout.is_empty());
         assert_eq!(output.status.code(), Some(1));
     }
+
+    #[test]
+    fn test_max_count_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["How", "tests/fixtures/poem.txt", "-m", "1", "--after", "1"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let clean_stdout = strip_ansi_color_codes(&stdout);
+
+        // The second match only appears as trailing context of the first
+        assert!(clean_stdout.contains("Line 6: How dreary to be somebody!"));
+        assert!(clean_stdout.contains("Line 7- How public, like a frog"));
+        assert!(!clean_stdout.contains("Line 8"));
+    }
+
+    #[test]
+    fn test_max_total_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "the",
+                "tests/fixtures",
+                "-r",
+                "-i",
+                "-c",
+                "--max-total",
+                "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let total: usize = stdout
+            .lines()
+            .map(|line| line.rsplit(':').next().unwrap().parse::<usize>().unwrap())
+            .sum();
+
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn test_group_separator() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody|frog", "tests/fixtures/poem.txt", "-C", "1"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let clean_stdout = strip_ansi_color_codes(&stdout);
+
+        assert_eq!(
+            clean_stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 1: I'm nobody! Who are you?\n\
+             Line 2: Are you nobody, too?\n\
+             Line 3- Then there's a pair of us - don't tell!\n\
+             --\n\
+             Line 6- How dreary to be somebody!\n\
+             Line 7: How public, like a frog\n\
+             Line 8- To tell your name the livelong day\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody|frog", "tests/fixtures/poem.txt", "-C", "1"])
+            .arg("--no-group-separator")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(!stdout.contains("--\n"));
+    }
+
+    #[test]
+    fn test_word_regexp_and_invert_flags() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["us", "tests/fixtures/poem.txt", "-w", "-c"])
+            .output()
+            .expect("Failed to execute command");
+
+        // "us" as a whole word, not inside "public"
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/poem.txt:2\n");
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["o", "tests/fixtures/poem.txt", "--invert-match"])
+            .output()
+            .expect("Failed to execute command");
+
+        // Only the blank line has no "o"
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "Matches in tests/fixtures/poem.txt:\nLine 5: \n");
+    }
+
+    #[test]
+    fn test_normalization_flags() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frög", "tests/fixtures/poem.txt", "--ignore-accents", "-c"])
+            .output()
+            .expect("Failed to execute command");
+
+        // Accents are stripped from the pattern as well as the lines
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/poem.txt:1\n");
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt", "--normalize=nfd"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Invalid normalization form: 'nfd'"));
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_pattern_flags() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["-e", "frog", "-e", "bog", "tests/fixtures/poem.txt", "-c"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "tests/fixtures/poem.txt:2\n");
+
+        // Patterns read from stdin, with matches attributed in the stats
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["-f", "-", "tests/fixtures/poem.txt", "--stats"])
+            .write_stdin("frog\n\nnobody\n")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("Patterns searched: 2"));
+        assert!(stdout.contains("'frog': 1 matching lines"));
+        assert!(stdout.contains("'nobody': 2 matching lines"));
+
+        // A repeated pattern is counted on its own, also when only counting
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["-f", "-", "tests/fixtures/poem.txt", "-c", "--stats"])
+            .write_stdin("frog\nnobody\nfrog\n")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(stdout.matches("'frog': 1 matching lines").count(), 2);
+        assert!(stdout.contains("'nobody': 2 matching lines"));
+    }
+
+    #[test]
+    fn test_boolean_query_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "--query",
+                "(nobody OR somebody) AND NOT \"Who are\"",
+                "tests/fixtures/poem.txt",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 2: Are you nobody, too?\n\
+             Line 6: How dreary to be somebody!\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["--query", "nobody AND (", "tests/fixtures/poem.txt"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_file_level_patterns() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["the", "tests/fixtures", "-r", "-l"])
+            .args(["--all-of", "over", "--none-of", "moon", "--stats"])
+            .output()
+            .expect("Failed to execute command");
+
+        // Only sunrise.txt mentions "over" without mentioning "moon"
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("tests/fixtures/sunrise.txt\n\n"));
+        assert!(stdout.contains("Files filtered out: 2"));
+
+        // A filtered out file has no matching lines, so -L lists it and -c
+        // counts zero for it
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "the",
+                "tests/fixtures/nightfall.txt",
+                "tests/fixtures/poem.txt",
+            ])
+            .args(["-L", "--none-of", "moon"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "tests/fixtures/nightfall.txt\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "the",
+                "tests/fixtures/nightfall.txt",
+                "tests/fixtures/poem.txt",
+            ])
+            .args(["-c", "--none-of", "moon"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "tests/fixtures/nightfall.txt:0\ntests/fixtures/poem.txt:2\n"
+        );
+    }
+
+    #[test]
+    fn test_near_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "-e",
+                "nobody",
+                "-e",
+                "banish",
+                "tests/fixtures/poem.txt",
+                "--near",
+                "2",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        // "nobody" on line 1 is too far from "banish" on line 4
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 2: Are you nobody, too?\n\
+             Line 3- Then there's a pair of us - don't tell!\n\
+             Line 4: They'd banish us, you know.\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "-e",
+                "nobody",
+                "-e",
+                "banish",
+                "tests/fixtures/poem.txt",
+                "--near",
+                "2",
+                "--invert-match",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("'--invert-match' can't be combined with '--near'")
+        );
+    }
+
+    #[test]
+    fn test_multiline_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["tell!\nThey'd", "tests/fixtures/poem.txt", "-U"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 3: Then there's a pair of us - don't tell!\n\
+             Line 4: They'd banish us, you know.\n"
+        );
+
+        // Without -U the pattern can never match a single line
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["tell!\nThey'd", "tests/fixtures/poem.txt"])
+            .assert()
+            .code(1);
+    }
+
+    #[test]
+    fn test_fuzzy_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobdy", "tests/fixtures/poem.txt", "--fuzzy", "1", "-s"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("Line 1: ~1 I'm nobody! Who are you?"));
+        assert!(stdout.contains("Line 2: ~1 Are you nobody, too?"));
+        assert!(stdout.contains("Matches by edit distance:\n  1: 2 matches"));
+
+        // JSON output gives the edit distance of every match
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobdy", "tests/fixtures/poem.txt", "--fuzzy", "1", "--json"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<serde_json::Value> = stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({
+                "file": "tests/fixtures/poem.txt",
+                "type": "match",
+                "line_number": 1,
+                "byte_offset": 0,
+                "text": "I'm nobody! Who are you?",
+                "matches": [{"start": 4, "end": 10, "text": "nobody", "edit_distance": 1}],
+            })
+        );
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobdy", "tests/fixtures/poem.txt", "--json", "-c"])
+            .assert()
+            .code(2);
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobdy", "tests/fixtures/poem.txt", "--fuzzy", "one"])
+            .assert()
+            .code(2);
+    }
+
+    #[test]
+    fn test_replace_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "nobody|frog",
+                "tests/fixtures/poem.txt",
+                "--replace",
+                "[$0]",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 1: I'm [nobody]! Who are you?\n\
+             Line 2: Are you [nobody], too?\n\
+             Line 7: How public, like a [frog]\n"
+        );
+
+        // The file itself is left untouched
+        let contents = std::fs::read_to_string("tests/fixtures/poem.txt").unwrap();
+        assert!(contents.contains("I'm nobody!"));
+    }
+
+    #[test]
+    fn test_in_place_flags() {
+        let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("in_place");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "call foo(1)\r\nkeep\ncall foo(2)\n").unwrap();
+
+        // A dry run prints the diff and leaves the file alone
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([r"foo\((\d)\)", file.to_str().unwrap()])
+            .args(["--replace", "bar($1)", "--in-place", "--dry-run"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("@@ -1,3 +1,3 @@\n-call foo(1)\n+call bar(1)\n keep\n"));
+        assert!(std::fs::read_to_string(&file).unwrap().contains("foo(1)"));
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([r"foo\((\d)\)", dir.to_str().unwrap(), "-r"])
+            .args(["--replace", "bar($1)", "--in-place", "--backup"])
+            .assert()
+            .success();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "call bar(1)\r\nkeep\ncall bar(2)\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("notes.txt.bak")).unwrap(),
+            "call foo(1)\r\nkeep\ncall foo(2)\n"
+        );
+
+        // Rewriting needs something to replace the matches with
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["foo", file.to_str().unwrap(), "--in-place"])
+            .assert()
+            .code(2);
+
+        // Modes that don't print lines would silently skip the rewrite
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["bar", file.to_str().unwrap(), "-c"])
+            .args(["--replace", "baz", "--in-place"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("'--in-place' can't be combined with '--count'")
+        );
+        assert!(std::fs::read_to_string(&file).unwrap().contains("bar(1)"));
+
+        // A multi-line record is diffed over all of its lines
+        let paragraphs = dir.join("paragraphs.txt");
+        std::fs::write(&paragraphs, "a foo\nb foo\n\nc\n").unwrap();
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["foo", paragraphs.to_str().unwrap(), "--paragraph"])
+            .args(["--replace", "X", "--in-place", "--dry-run"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("@@ -1,4 +1,4 @@\n-a foo\n-b foo\n+a X\n+b X\n \n c\n"));
+    }
+
+    #[test]
+    fn test_column_and_byte_offset_flags() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody", "tests/fixtures/poem.txt", "--column", "-b"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Line 1, column 5, byte 0: I'm nobody! Who are you?\n\
+             Line 2, column 9, byte 25: Are you nobody, too?\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody", "tests/fixtures/poem.txt", "-o", "--column", "-b"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(stdout, "5:4:nobody\n9:33:nobody\n");
+
+        // Replaced matches are located where they were in the file
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["nobody|you", "tests/fixtures/poem.txt", "--replace", "X"])
+            .args(["-o", "--column", "-b"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "5:4:X\n21:20:X\n5:29:X\n9:33:X\n19:104:X\n9:174:X\n"
+        );
+    }
+
+    #[test]
+    fn test_crlf_flags() {
+        let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("crlf");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("windows.txt");
+        std::fs::write(&file, "end;\r\nfoo end;\r\nlast").unwrap();
+        let path = file.to_str().unwrap();
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["end;$", path, "-U"])
+            .assert()
+            .code(1);
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "end;$",
+                path,
+                "-U",
+                "--crlf",
+                "--preserve-line-endings",
+                "-s",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.starts_with(&format!(
+            "Matches in {path}:\nLine 1: end;\r\nLine 2: foo end;\r\n"
+        )));
+        assert!(stdout.contains(&format!("Files by line ending: 1 CRLF\n  {path}: CRLF\n")));
+
+        // A last line without a terminator doesn't run into the next file
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["last|frog", path, "tests/fixtures/poem.txt"])
+            .arg("--preserve-line-endings")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("Line 3: last\nMatches in tests/fixtures/poem.txt:\n"));
+
+        // Paragraph and record separators aren't line endings to preserve
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt", "--paragraph"])
+            .arg("--preserve-line-endings")
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("'--preserve-line-endings' can't be combined with '--paragraph'")
+        );
+    }
+
+    #[test]
+    fn test_null_flags() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "frog",
+                "tests/fixtures/poem.txt",
+                "tests/fixtures/sunrise.txt",
+            ])
+            .args(["-l", "-0"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "tests/fixtures/poem.txt\0"
+        );
+
+        // Names followed by a count or a match end with NUL instead of `:`
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt", "-c", "-0"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "tests/fixtures/poem.txt\x001\n"
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt", "-o", "-H", "-0"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(stdout, "tests/fixtures/poem.txt\0frog\n");
+
+        let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("null_data");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("records.bin");
+        std::fs::write(&file, "one\nfoo\0two\0").unwrap();
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["foo", file.to_str().unwrap(), "--null-data", "-c"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("{}:1\n", file.display())
+        );
+    }
+
+    #[test]
+    fn test_paragraph_flag() {
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["frog", "tests/fixtures/poem.txt", "--paragraph"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            "Matches in tests/fixtures/poem.txt:\n\
+             Record 2: How dreary to be somebody!\n\
+             How public, like a frog\n\
+             To tell your name the livelong day\n\
+             To an admiring bog!\n"
+        );
+    }
+
+    #[test]
+    fn test_json_field_flags() {
+        let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("json_field");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("app.log");
+        std::fs::write(
+            &file,
+            "{\"ts\":\"10:00\",\"level\":\"info\",\"msg\":\"started\"}\n\
+             {\"ts\":\"10:01\",\"level\":\"error\",\"msg\":\"db timeout\",\"ctx\":{\"user\":\"al\"}}\n\
+             not json: level=error timeout\n",
+        )
+        .unwrap();
+        let path = file.to_str().unwrap();
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "--json-field",
+                "level=error",
+                "--json-field",
+                "msg~time",
+                path,
+            ])
+            .args(["--fields", "ts,ctx.user,host"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            format!(
+                "Matches in {path}:\nLine 2: {{\"ts\":\"10:01\",\"ctx.user\":\"al\",\"host\":null}}\n"
+            )
+        );
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["started", path, "--json-field", "level=info", "--pretty"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            format!(
+                "Matches in {path}:\nLine 1: {{\n  \"ts\": \"10:00\",\n  \"level\": \"info\",\n  \
+                 \"msg\": \"started\"\n}}\n"
+            )
+        );
+
+        // Without a pattern, the stats show the predicates instead
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["--json-field", "level=error", path, "-c", "--stats"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert!(stdout.contains("Field predicates: level=error\n"));
+        assert!(!stdout.contains("Pattern searched"));
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["--json-field", "level", path])
+            .assert()
+            .code(2);
+    }
+
+    #[test]
+    fn test_table_flags() {
+        let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("table");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("tickets.csv");
+        std::fs::write(
+            &file,
+            "id,title,status\n\
+             1,\"Fix timeout, again\",open\n\
+             2,Docs,\"blocked:\ntimeout in CI\"\n\
+             3,timeout,closed\n",
+        )
+        .unwrap();
+        let path = file.to_str().unwrap();
+
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["timeout", path, "--csv", "--column-name", "status"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = strip_ansi_color_codes(&String::from_utf8(output.stdout).unwrap());
+        assert_eq!(
+            stdout,
+            format!(
+                "Matches in {path}:\n\
+                 Row 1- id,title,status\n\
+                 Row 3: 2,Docs,\"blocked:\ntimeout in CI\"\n"
+            )
+        );
+
+        // Matching rows are printed as CSV, quoting and all
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args([
+                "timeout",
+                path,
+                "--csv",
+                "--column-index",
+                "2",
+                "--csv-output",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "id,title,status\n1,\"Fix timeout, again\",open\n3,timeout,closed\n"
+        );
+
+        // Quotes doubled in a quoted field are matched as one, and printed as written
+        let quoted = dir.join("quotes.csv");
+        std::fs::write(&quoted, "id,title\n1,\"say \"\"hi\"\"\"\n2,hi\n").unwrap();
+        let output = Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["say \"hi\"", quoted.to_str().unwrap(), "--csv"])
+            .args(["--column-name", "title", "--csv-output"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "id,title\n1,\"say \"\"hi\"\"\"\n"
+        );
+
+        Command::cargo_bin("minigrep")
+            .unwrap()
+            .args(["timeout", path, "--column-name", "status"])
+            .assert()
+            .code(2);
+    }
 }